@@ -11,7 +11,7 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use crate::{RCodec, WCodec, Zenoh080};
+use crate::{RCodec, WCodec, Zenoh080, Zenoh080Bounded};
 use zenoh_buffers::{
     reader::{DidntRead, Reader},
     writer::{DidntWrite, Writer},
@@ -49,7 +49,12 @@ where
     fn read(self, reader: &mut R) -> Result<SharedMemoryBufInfo, Self::Error> {
         let offset: usize = self.read(&mut *reader)?;
         let length: usize = self.read(&mut *reader)?;
-        let shm_manager: String = self.read(&mut *reader)?;
+        // Reject a buffer that can not fit in any segment
+        if offset.checked_add(length).is_none() {
+            return Err(DidntRead);
+        }
+        // Bound the segment path so that a malformed length can not trigger a huge allocation
+        let shm_manager: String = Zenoh080Bounded::<u16>::new().read(&mut *reader)?;
         let kind: u8 = self.read(&mut *reader)?;
 
         let shm_info = SharedMemoryBufInfo::new(offset, length, shm_manager, kind);
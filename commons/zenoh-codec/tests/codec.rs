@@ -331,14 +331,54 @@ fn codec_shm_info() {
         let mut rng = rand::thread_rng();
         let len = rng.gen_range(0..16);
         SharedMemoryBufInfo::new(
-            rng.gen(),
-            rng.gen(),
+            rng.gen::<u32>() as usize,
+            rng.gen::<u32>() as usize,
             Alphanumeric.sample_string(&mut rng, len),
             rng.gen(),
         )
     });
 }
 
+#[cfg(feature = "shared-memory")]
+#[test]
+fn codec_shm_info_malformed() {
+    use zenoh_buffers::writer::Writer;
+    use zenoh_shm::SharedMemoryBufInfo;
+
+    let codec = Zenoh080::new();
+    let mut rng = rand::thread_rng();
+
+    // Random bytes never make the decoding panic
+    for _ in 0..NUM_ITER * 100 {
+        let len = rng.gen_range(0..MAX_PAYLOAD_SIZE);
+        let bytes: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+        let mut reader = bytes.reader();
+        let _: Result<SharedMemoryBufInfo, _> = codec.read(&mut reader);
+    }
+
+    // A buffer ending beyond the addressable memory is rejected
+    let mut buff = vec![];
+    let mut writer = buff.writer();
+    codec.write(&mut writer, usize::MAX).unwrap();
+    codec.write(&mut writer, 1usize).unwrap();
+    codec.write(&mut writer, "segment").unwrap();
+    codec.write(&mut writer, 0u8).unwrap();
+    let mut reader = buff.reader();
+    let info: Result<SharedMemoryBufInfo, _> = codec.read(&mut reader);
+    assert!(info.is_err());
+
+    // A path length larger than any path is rejected before allocating
+    let mut buff = vec![];
+    let mut writer = buff.writer();
+    codec.write(&mut writer, 0usize).unwrap();
+    codec.write(&mut writer, 1usize).unwrap();
+    codec.write(&mut writer, u64::MAX).unwrap();
+    writer.write_u8(0).unwrap();
+    let mut reader = buff.reader();
+    let info: Result<SharedMemoryBufInfo, _> = codec.read(&mut reader);
+    assert!(info.is_err());
+}
+
 // Common
 #[test]
 fn codec_extension() {
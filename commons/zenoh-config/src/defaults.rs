@@ -218,7 +218,10 @@ impl Default for LinkRxConf {
 #[allow(clippy::derivable_impls)]
 impl Default for SharedMemoryConf {
     fn default() -> Self {
-        Self { enabled: false }
+        Self {
+            enabled: false,
+            auth_token: None,
+        }
     }
 }
 
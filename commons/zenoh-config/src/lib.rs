@@ -452,6 +452,10 @@ validated_struct::validator! {
                 /// If set to `true`, the SHM buffer optimization support will be announced to other parties. (default `false`).
                 /// This option doesn't make SHM buffer optimization mandatory, the real support depends on other party setting
                 enabled: bool,
+                /// A pre-shared token both parties must present to use the SHM buffer optimization with each other.
+                /// If set, SHM is declined with the parties presenting another token or none, without failing the session
+                /// establishment. If not set, the token presented by the other party (if any) is ignored. (default `null`).
+                auth_token: Option<String>,
             },
             pub auth: #[derive(Default)]
             AuthConf {
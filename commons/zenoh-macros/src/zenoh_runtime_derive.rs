@@ -109,6 +109,8 @@ fn generate_declare_param(
         .iter()
         .map(|name| format_ident!("DefaultParamOf{}", name))
         .collect();
+    // The runtime the others are handed over to when unified
+    let first_variant = variant_names[0];
     let params_with_default = params.iter().map(|x| {
         if x.to_string() != "" {
             quote!(#x, ..Default::default())
@@ -137,23 +139,40 @@ fn generate_declare_param(
         )*
 
         // An internal helper struct for parsing the RuntimeParam
-        #[derive(Deserialize, Debug, Clone, Copy)]
+        #[derive(Deserialize, Debug, Clone)]
         #[serde(deny_unknown_fields)]
         struct AbstractRuntimeParam {
             #(
                 #[serde(default)]
                 #aliases: RuntimeParamHelper<#default_param_of_variant>,
             )*
+            #[serde(default)]
+            max_total_worker_threads: Option<usize>,
+            #[serde(default)]
+            unified: bool,
         }
 
-        // AbstractRuntimeParam => GlobalRuntimeParam, extract fields from AbstractRuntimeParam
-        impl From<AbstractRuntimeParam> for GlobalRuntimeParam {
-            fn from(value: AbstractRuntimeParam) -> Self {
-                Self {
+        // AbstractRuntimeParam => GlobalRuntimeParam, extract fields from AbstractRuntimeParam,
+        // validate them, then unify them and scale them down to the global limits if any
+        impl TryFrom<AbstractRuntimeParam> for GlobalRuntimeParam {
+            type Error = zenoh_result::Error;
+
+            fn try_from(value: AbstractRuntimeParam) -> zenoh_result::ZResult<Self> {
+                let mut param = Self {
                     #(
                         #aliases: value.#aliases.into(),
                     )*
+                };
+                #(
+                    param.#aliases.validate(#variant_names)?;
+                )*
+                if value.unified {
+                    #meta_param::unify(&mut [#((#variant_names, &mut param.#aliases),)*], #first_variant);
+                }
+                if let Some(max) = value.max_total_worker_threads {
+                    #meta_param::cap_worker_threads(&mut [#(&mut param.#aliases,)*], max);
                 }
+                Ok(param)
             }
         }
 
@@ -210,8 +229,9 @@ pub(crate) fn derive_register_param(input: DeriveInput) -> Result<TokenStream, s
             pub static ref ZRUNTIME_PARAM: GlobalRuntimeParam = Options::default()
                 .with_default_extension(Extensions::IMPLICIT_SOME)
                 .from_str::<AbstractRuntimeParam>(&ZENOH_RUNTIME_ENV_STRING)
-                .unwrap()
-                .into();
+                .map_err(Into::into)
+                .and_then(GlobalRuntimeParam::try_from)
+                .unwrap_or_else(|e| panic!("Invalid {}: {}", ZENOH_RUNTIME_ENV, e));
         }
 
         #declare_param_quote
@@ -291,7 +311,7 @@ pub(crate) fn derive_generic_runtime_param(input: DeriveInput) -> Result<TokenSt
         use std::marker::PhantomData;
 
         // Declare a helper struct to be generic over any T implementing DefaultParam
-        #[derive(Deserialize, Debug, Clone, Copy)]
+        #[derive(Deserialize, Debug, Clone)]
         #[serde(deny_unknown_fields, default)]
         struct #helper_name<T>
         where
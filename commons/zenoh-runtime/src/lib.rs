@@ -12,28 +12,34 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use core::panic;
+use futures::FutureExt;
 use lazy_static::lazy_static;
 use serde::Deserialize;
 use std::{
+    any::Any,
     borrow::Borrow,
     collections::HashMap,
     env,
     future::Future,
     ops::Deref,
+    panic::AssertUnwindSafe,
     sync::{
         atomic::{AtomicUsize, Ordering},
-        OnceLock,
+        Arc, Mutex, OnceLock, PoisonError,
     },
     time::Duration,
 };
-use tokio::runtime::{Handle, Runtime, RuntimeFlavor};
+use tokio::{
+    runtime::{Handle, Runtime, RuntimeFlavor, RuntimeMetrics},
+    task::JoinHandle,
+};
 use zenoh_macros::{GenericRuntimeParam, RegisterParam};
-use zenoh_result::ZResult as Result;
+use zenoh_result::{bail, zerror, ZResult as Result};
 
 pub const ZENOH_RUNTIME_ENV: &str = "ZENOH_RUNTIME";
 
 /// Available parameters to configure the ZRuntime.
-#[derive(Deserialize, Debug, GenericRuntimeParam)]
+#[derive(Deserialize, Debug, Clone, GenericRuntimeParam)]
 #[serde(deny_unknown_fields, default)]
 pub struct RuntimeParam {
     /// Number of async worker threads. At least one.
@@ -42,6 +48,11 @@ pub struct RuntimeParam {
     pub max_blocking_threads: usize,
     /// Hand over one ZRuntime to another one.
     pub handover: Option<ZRuntime>,
+    /// Stack size in bytes of the worker and blocking threads. Tokio's default if not set.
+    pub thread_stack_size: Option<usize>,
+    /// Cores the worker and blocking threads are pinned to, in a round-robin fashion. The OS
+    /// scheduler places the threads if not set or empty. Only supported on Linux.
+    pub core_ids: Option<Vec<usize>>,
 }
 
 impl Default for RuntimeParam {
@@ -50,13 +61,72 @@ impl Default for RuntimeParam {
             worker_threads: 1,
             max_blocking_threads: 50,
             handover: None,
+            thread_stack_size: None,
+            core_ids: None,
         }
     }
 }
 
 impl RuntimeParam {
+    // Scales down the worker threads of the given runtimes proportionally so that their total
+    // does not exceed `max`. Runtimes handed over to another one are not built, so they are left
+    // out. Each runtime keeps at least one worker thread, so the total may exceed a `max` lower
+    // than the number of runtimes.
+    fn cap_worker_threads(params: &mut [&mut RuntimeParam], max: usize) {
+        let total: usize = params
+            .iter()
+            .filter(|p| p.handover.is_none())
+            .map(|p| p.worker_threads)
+            .sum();
+        if total <= max {
+            return;
+        }
+        for param in params.iter_mut().filter(|p| p.handover.is_none()) {
+            param.worker_threads = (param.worker_threads * max / total).max(1);
+        }
+    }
+
+    // Hands over all the runtimes to `zrt`, so that a single runtime is built and shared by all
+    // of them.
+    fn unify(params: &mut [(ZRuntime, &mut RuntimeParam)], zrt: ZRuntime) {
+        for (z, param) in params.iter_mut() {
+            param.handover = (*z != zrt).then_some(zrt);
+        }
+    }
+
+    // Tokio panics on zero thread counts, report it as a configuration error instead
+    fn validate(&self, zrt: ZRuntime) -> Result<()> {
+        if self.worker_threads == 0 {
+            bail!(
+                "Invalid worker_threads for {}: at least one is required",
+                zrt
+            );
+        }
+        if self.max_blocking_threads == 0 {
+            bail!(
+                "Invalid max_blocking_threads for {}: at least one is required",
+                zrt
+            );
+        }
+        Ok(())
+    }
+
     pub fn build(&self, zrt: ZRuntime) -> Result<Runtime> {
-        let rt = tokio::runtime::Builder::new_multi_thread()
+        self.build_with_callbacks(zrt, THREAD_CALLBACKS.get())
+    }
+
+    fn build_with_callbacks(
+        &self,
+        zrt: ZRuntime,
+        callbacks: Option<&ThreadCallbacks>,
+    ) -> Result<Runtime> {
+        self.validate(zrt)?;
+        let core_ids = self.core_ids.clone().filter(|ids| !ids.is_empty());
+        if let Some(id) = core_ids.iter().flatten().find(|id| !is_valid_core_id(**id)) {
+            bail!("Invalid core_ids for {}: core {} does not exist", zrt, id);
+        }
+        let mut builder = tokio::runtime::Builder::new_multi_thread();
+        builder
             .worker_threads(self.worker_threads)
             .max_blocking_threads(self.max_blocking_threads)
             .enable_io()
@@ -67,12 +137,101 @@ impl RuntimeParam {
                     .unwrap()
                     .fetch_add(1, Ordering::SeqCst);
                 format!("{}-{}", zrt, id)
-            })
-            .build()?;
+            });
+        if let Some(thread_stack_size) = self.thread_stack_size {
+            builder.thread_stack_size(thread_stack_size);
+        }
+        let on_thread_start = callbacks.and_then(|c| c.on_thread_start.clone());
+        if core_ids.is_some() || on_thread_start.is_some() {
+            let next = AtomicUsize::new(0);
+            builder.on_thread_start(move || {
+                if let Some(ids) = &core_ids {
+                    let id = ids[next.fetch_add(1, Ordering::Relaxed) % ids.len()];
+                    pin_current_thread(id);
+                }
+                if let Some(f) = &on_thread_start {
+                    f();
+                }
+            });
+        }
+        if let Some(f) = callbacks.and_then(|c| c.on_thread_stop.clone()) {
+            builder.on_thread_stop(move || f());
+        }
+        let rt = builder.build()?;
         Ok(rt)
     }
 }
 
+#[cfg(target_os = "linux")]
+fn is_valid_core_id(id: usize) -> bool {
+    id < libc::CPU_SETSIZE as usize
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_valid_core_id(_id: usize) -> bool {
+    true
+}
+
+// Pins the current thread to the given core. The thread is left where the OS scheduler placed it
+// if the core is not available, e.g. if excluded from the process affinity.
+#[cfg(target_os = "linux")]
+fn pin_current_thread(id: usize) {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(id, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(_id: usize) {}
+
+/// Returns the message of a caught panic, e.g. from [`std::panic::catch_unwind`] or a
+/// [`tokio::task::JoinError`], if its payload is a string.
+pub fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg
+    } else {
+        "unknown panic payload"
+    }
+}
+
+type ThreadCallback = Arc<dyn Fn() + Send + Sync>;
+
+/// Callbacks run by each thread of the ZRuntimes, e.g. to register the threads with a profiler.
+///
+/// They apply to both the worker and the blocking threads of all the runtimes and are set with
+/// [`ZRuntimePool::set_thread_callbacks`].
+#[derive(Clone, Default)]
+pub struct ThreadCallbacks {
+    on_thread_start: Option<ThreadCallback>,
+    on_thread_stop: Option<ThreadCallback>,
+}
+
+impl ThreadCallbacks {
+    /// Run `f` on each thread after it starts and before it does any work.
+    pub fn on_thread_start<F>(mut self, f: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.on_thread_start = Some(Arc::new(f));
+        self
+    }
+
+    /// Run `f` on each thread before it stops.
+    pub fn on_thread_stop<F>(mut self, f: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.on_thread_stop = Some(Arc::new(f));
+        self
+    }
+}
+
+static THREAD_CALLBACKS: OnceLock<ThreadCallbacks> = OnceLock::new();
+
 /// [`ZRuntime`], the access point of manipulate runtimes within zenoh.
 /// The runtime parameter can be configured by setting the environmental variable [`ZENOH_RUNTIME_ENV`].
 /// The parsing syntax use [RON](https://github.com/ron-rs/ron). An example configuration looks
@@ -86,6 +245,34 @@ impl RuntimeParam {
 ///   tx: (max_blocking_threads: 1)
 /// )'
 /// ```
+/// The total number of worker threads of all the runtimes can be capped with
+/// `max_total_worker_threads`, e.g. on CPU-constrained hosts. When the sum of the configured
+/// worker threads exceeds it, each runtime gets a proportionally reduced number of worker
+/// threads, at least one.
+///
+/// ```console
+/// ZENOH_RUNTIME='(max_total_worker_threads: 4, rx: (worker_threads: 4))'
+/// ```
+/// The stack size of the threads of a runtime can be raised with `thread_stack_size`, e.g. for
+/// the application callbacks running on `app` to use deeper stacks than the other runtimes.
+///
+/// ```console
+/// ZENOH_RUNTIME='(app: (thread_stack_size: 8388608))'
+/// ```
+/// The threads of latency-sensitive runtimes can be pinned to specific cores with `core_ids`,
+/// the threads being spread over the given cores in a round-robin fashion. Only supported on
+/// Linux.
+///
+/// ```console
+/// ZENOH_RUNTIME='(rx: (core_ids: [2, 3]), tx: (core_ids: [4]))'
+/// ```
+/// On resource-constrained devices, all the runtimes can be collapsed into a single one with
+/// `unified`. Every ZRuntime is then handed over to `app`, whose parameters size the shared
+/// runtime, and its threads are named after `app`.
+///
+/// ```console
+/// ZENOH_RUNTIME='(unified: true, app: (worker_threads: 2))'
+/// ```
 /// Note: The runtime parameter takes effect at the beginning of the zenoh process and no longer be
 /// changed after the initialization.
 #[derive(Hash, Eq, PartialEq, Clone, Copy, Debug, RegisterParam, Deserialize)]
@@ -129,6 +316,62 @@ impl ZRuntime {
         }
         tokio::task::block_in_place(move || self.block_on(f))
     }
+
+    /// Spawns a future onto the runtime backing this [`ZRuntime`].
+    ///
+    /// Unlike [`Handle::spawn`], a panic of the task is logged along with the name of the
+    /// ZRuntime, so that it does not go unnoticed if the [`JoinHandle`] is dropped. The panic is
+    /// then propagated to the [`JoinHandle`] as usual.
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let zrt = *self;
+        ZRUNTIME_POOL.get(self).spawn(async move {
+            match AssertUnwindSafe(future).catch_unwind().await {
+                Ok(output) => output,
+                Err(payload) => {
+                    tracing::error!(
+                        "A task panicked on ZRuntime {}: {}",
+                        zrt,
+                        panic_message(payload.as_ref())
+                    );
+                    std::panic::resume_unwind(payload)
+                }
+            }
+        })
+    }
+
+    /// Returns the parameters the runtime backing this [`ZRuntime`] has been built with,
+    /// or `None` if it has not been initialized yet.
+    ///
+    /// The parameters are read from [`ZENOH_RUNTIME_ENV`] only once, upon the first access to
+    /// any ZRuntime. Changing the environmental variable afterwards has no effect, and this
+    /// method reports the values actually in use. If the runtime is handed over to another one,
+    /// the parameters of the latter are returned.
+    pub fn effective_param(&self) -> Option<RuntimeParam> {
+        ZRUNTIME_POOL.effective_param(self)
+    }
+
+    /// Returns the metrics of the runtime backing this [`ZRuntime`], e.g. to detect which runtime
+    /// is saturated, or `None` if it has not been initialized yet.
+    ///
+    /// Only the number of workers and of alive tasks are stable, the other tokio runtime metrics
+    /// require building with `RUSTFLAGS="--cfg tokio_unstable"`. If the runtime is handed over to
+    /// another one, the metrics of the latter are returned.
+    pub fn metrics(&self) -> Option<RuntimeMetrics> {
+        ZRUNTIME_POOL.metrics(self)
+    }
+
+    /// Shuts down the runtime backing this [`ZRuntime`], if initialized, waiting at most
+    /// `timeout` for its tasks to yield and its blocking tasks to complete.
+    ///
+    /// See [`ZRuntimePool::shutdown_timeout`]. If other ZRuntimes are handed over to the same
+    /// runtime, they are shut down as well.
+    pub fn shutdown_timeout(&self, timeout: Duration) {
+        ZRUNTIME_POOL.shutdown_runtime(self, timeout)
+    }
 }
 
 impl Deref for ZRuntime {
@@ -159,43 +402,122 @@ impl Drop for ZRuntimePoolGuard {
     }
 }
 
-pub struct ZRuntimePool(HashMap<ZRuntime, OnceLock<Runtime>>);
+pub struct ZRuntimePool(HashMap<ZRuntime, OnceLock<RuntimeSlot>>);
+
+// A runtime along with its handle, which stays valid once the runtime has been shut down
+struct RuntimeSlot {
+    handle: Handle,
+    runtime: Mutex<Option<Runtime>>,
+}
+
+impl RuntimeSlot {
+    fn take(&self) -> Option<Runtime> {
+        self.runtime
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+}
 
 impl ZRuntimePool {
     fn new() -> Self {
         Self(ZRuntime::iter().map(|zrt| (zrt, OnceLock::new())).collect())
     }
 
-    pub fn get(&self, zrt: &ZRuntime) -> &Handle {
-        // Although the ZRuntime is called to use `zrt`, it may be handed over to another one
-        // specified via the environmental variable.
+    // Although the ZRuntime is called to use `zrt`, it may be handed over to another one
+    // specified via the environmental variable.
+    fn resolve(zrt: &ZRuntime) -> ZRuntime {
         let param: &RuntimeParam = zrt.borrow();
-        let zrt = match param.handover {
+        match param.handover {
             Some(handover) => handover,
             None => *zrt,
-        };
+        }
+    }
+
+    fn effective_param(&self, zrt: &ZRuntime) -> Option<RuntimeParam> {
+        let zrt = Self::resolve(zrt);
+        self.0.get(&zrt)?.get()?;
+        let param: &RuntimeParam = zrt.borrow();
+        Some(param.clone())
+    }
+
+    fn metrics(&self, zrt: &ZRuntime) -> Option<RuntimeMetrics> {
+        let zrt = Self::resolve(zrt);
+        let slot = self.0.get(&zrt)?.get()?;
+        Some(slot.handle.metrics())
+    }
+
+    pub fn get(&self, zrt: &ZRuntime) -> &Handle {
+        let zrt = Self::resolve(zrt);
 
-        self.0
+        &self
+            .0
             .get(&zrt)
             .unwrap_or_else(|| panic!("The hashmap should contains {zrt} after initialization"))
             .get_or_init(|| {
-                zrt.init()
-                    .unwrap_or_else(|_| panic!("Failed to init {zrt}"))
+                let runtime = zrt
+                    .init()
+                    .unwrap_or_else(|e| panic!("Failed to init {zrt}: {e}"));
+                RuntimeSlot {
+                    handle: runtime.handle().clone(),
+                    runtime: Mutex::new(Some(runtime)),
+                }
             })
-            .handle()
+            .handle
     }
-}
 
-// If there are any blocking tasks spawned by ZRuntimes, the function will block until they return.
-impl Drop for ZRuntimePool {
-    fn drop(&mut self) {
-        let handles: Vec<_> = self
+    /// Sets the callbacks run by each thread of the runtimes.
+    ///
+    /// The callbacks are passed to the runtimes upon their creation, hence this must be called
+    /// before any ZRuntime is used. An error is returned if a runtime has already been
+    /// initialized or if the callbacks have already been set.
+    pub fn set_thread_callbacks(&self, callbacks: ThreadCallbacks) -> Result<()> {
+        if let Some(zrt) = self
             .0
-            .drain()
-            .filter_map(|(_name, mut rt)| {
-                rt.take()
-                    .map(|r| std::thread::spawn(move || r.shutdown_timeout(Duration::from_secs(1))))
-            })
+            .iter()
+            .find_map(|(zrt, slot)| slot.get().map(|_| zrt))
+        {
+            bail!(
+                "Unable to set the thread callbacks: {} is already initialized",
+                zrt
+            );
+        }
+        THREAD_CALLBACKS
+            .set(callbacks)
+            .map_err(|_| zerror!("The thread callbacks are already set"))?;
+        Ok(())
+    }
+
+    /// Shuts down all the initialized runtimes, waiting at most `timeout` for their tasks to
+    /// yield and their blocking tasks to complete, e.g. to let in-flight I/O drain before the
+    /// process exits. The runtimes are shut down concurrently, and the runtimes never used are
+    /// not created.
+    ///
+    /// The tasks still pending once shut down are dropped. The ZRuntimes must not be used
+    /// afterwards: new tasks are dropped right away and blocking on them panics.
+    pub fn shutdown_timeout(&self, timeout: Duration) {
+        Self::shutdown_runtimes(
+            self.0.values().filter_map(|slot| slot.get()?.take()),
+            timeout,
+        );
+    }
+
+    fn shutdown_runtime(&self, zrt: &ZRuntime, timeout: Duration) {
+        let zrt = Self::resolve(zrt);
+        Self::shutdown_runtimes(
+            self.0
+                .get(&zrt)
+                .and_then(OnceLock::get)
+                .and_then(RuntimeSlot::take),
+            timeout,
+        );
+    }
+
+    // Runtimes can not be shut down from an async context, hence a thread per runtime
+    fn shutdown_runtimes(runtimes: impl IntoIterator<Item = Runtime>, timeout: Duration) {
+        let handles: Vec<_> = runtimes
+            .into_iter()
+            .map(|r| std::thread::spawn(move || r.shutdown_timeout(timeout)))
             .collect();
 
         for hd in handles {
@@ -204,9 +526,264 @@ impl Drop for ZRuntimePool {
     }
 }
 
+// If there are any blocking tasks spawned by ZRuntimes, the function will block until they return.
+impl Drop for ZRuntimePool {
+    fn drop(&mut self) {
+        self.shutdown_timeout(Duration::from_secs(1));
+    }
+}
+
 #[should_panic(expected = "Zenoh runtime doesn't support")]
 #[tokio::test]
 async fn block_in_place_fail_test() {
     use crate::ZRuntime;
     ZRuntime::TX.block_in_place(async { println!("Done") });
 }
+
+#[test]
+fn spawn_panic_test() {
+    use crate::ZRuntime;
+    let task = ZRuntime::Application.spawn(async { panic!("Boom") });
+    let err = ZRuntime::Application
+        .block_on(task)
+        .expect_err("The panic should be propagated");
+    assert_eq!(panic_message(err.into_panic().as_ref()), "Boom");
+}
+
+#[test]
+fn effective_param_test() {
+    use crate::ZRuntime;
+    ZRuntime::Net.block_on(async {});
+    let before = ZRuntime::Net.effective_param().unwrap();
+
+    // Changing the configuration after the initialization has no effect
+    env::set_var(ZENOH_RUNTIME_ENV, "(net: (worker_threads: 7))");
+    let after = ZRuntime::Net.effective_param().unwrap();
+    assert_eq!(before.worker_threads, after.worker_threads);
+    assert_ne!(after.worker_threads, 7);
+}
+
+#[test]
+fn metrics_test() {
+    use crate::ZRuntime;
+    let pool = ZRuntimePool::new();
+    assert!(pool.metrics(&ZRuntime::RX).is_none());
+
+    pool.get(&ZRuntime::RX);
+    let zrt = ZRuntimePool::resolve(&ZRuntime::RX);
+    let param: &RuntimeParam = zrt.borrow();
+    let metrics = pool.metrics(&ZRuntime::RX).unwrap();
+    assert_eq!(metrics.num_workers(), param.worker_threads);
+    assert_eq!(metrics.num_alive_tasks(), 0);
+    pool.get(&ZRuntime::RX).spawn(std::future::pending::<()>());
+    assert_eq!(metrics.num_alive_tasks(), 1);
+    pool.shutdown_timeout(Duration::from_secs(1));
+}
+
+#[test]
+fn shutdown_timeout_test() {
+    use crate::ZRuntime;
+    let pool = ZRuntimePool::new();
+    let guard = Arc::new(());
+    let c_guard = guard.clone();
+    pool.get(&ZRuntime::TX).spawn(async move {
+        let _guard = c_guard;
+        std::future::pending::<()>().await
+    });
+
+    // The pending tasks are dropped and the runtimes never used are not created
+    pool.shutdown_timeout(Duration::from_secs(1));
+    assert_eq!(Arc::strong_count(&guard), 1);
+    assert!(pool.0[&ZRuntime::RX].get().is_none());
+
+    // Shutting down again has no effect
+    pool.shutdown_timeout(Duration::from_secs(1));
+}
+
+#[test]
+fn thread_stack_size_test() {
+    use crate::ZRuntime;
+    let param = RuntimeParam {
+        thread_stack_size: Some(16 << 20),
+        ..Default::default()
+    };
+    let rt = param.build(ZRuntime::TX).unwrap();
+    // The task uses more stack than the 2 MiB tokio provides by default
+    let task = rt.spawn(async {
+        let buffer = [1u8; 4 << 20];
+        std::hint::black_box(&buffer)
+            .iter()
+            .map(|b| *b as usize)
+            .sum::<usize>()
+    });
+    assert_eq!(rt.block_on(task).unwrap(), 4 << 20);
+}
+
+#[test]
+fn thread_callbacks_test() {
+    use crate::ZRuntime;
+    let started = Arc::new(AtomicUsize::new(0));
+    let stopped = Arc::new(AtomicUsize::new(0));
+    let callbacks = ThreadCallbacks::default()
+        .on_thread_start({
+            let started = started.clone();
+            move || {
+                started.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .on_thread_stop({
+            let stopped = stopped.clone();
+            move || {
+                stopped.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+    let param = RuntimeParam {
+        worker_threads: 3,
+        ..Default::default()
+    };
+    let rt = param
+        .build_with_callbacks(ZRuntime::TX, Some(&callbacks))
+        .unwrap();
+
+    // The worker threads are started in the background
+    let now = std::time::Instant::now();
+    while started.load(Ordering::SeqCst) < 3 && now.elapsed() < Duration::from_secs(5) {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(started.load(Ordering::SeqCst), 3);
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+    assert_eq!(stopped.load(Ordering::SeqCst), 3);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn core_ids_test() {
+    use crate::ZRuntime;
+    let param = RuntimeParam {
+        worker_threads: 2,
+        core_ids: Some(vec![0]),
+        ..Default::default()
+    };
+    let rt = param.build(ZRuntime::TX).unwrap();
+    let cores = rt.block_on(async {
+        tokio::spawn(async {
+            let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+            unsafe {
+                libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set);
+                (0..libc::CPU_SETSIZE as usize)
+                    .filter(|id| libc::CPU_ISSET(*id, &set))
+                    .collect::<Vec<_>>()
+            }
+        })
+        .await
+        .unwrap()
+    });
+    assert_eq!(cores, vec![0]);
+
+    let param = RuntimeParam {
+        core_ids: Some(vec![usize::MAX]),
+        ..Default::default()
+    };
+    assert!(param.build(ZRuntime::TX).is_err());
+}
+
+#[test]
+fn zero_threads_test() {
+    use crate::ZRuntime;
+    for param in [
+        RuntimeParam {
+            worker_threads: 0,
+            ..Default::default()
+        },
+        RuntimeParam {
+            max_blocking_threads: 0,
+            ..Default::default()
+        },
+    ] {
+        assert!(param.build(ZRuntime::TX).is_err());
+    }
+
+    // Reported when ZENOH_RUNTIME is parsed, before any runtime is built
+    for env in [
+        "(tx: (worker_threads: 0))",
+        "(rx: (max_blocking_threads: 0))",
+    ] {
+        let param = Options::default()
+            .with_default_extension(Extensions::IMPLICIT_SOME)
+            .from_str::<AbstractRuntimeParam>(env)
+            .unwrap();
+        assert!(GlobalRuntimeParam::try_from(param).is_err());
+    }
+}
+
+#[test]
+fn unify_test() {
+    use crate::ZRuntime;
+    let param = |handover| RuntimeParam {
+        handover,
+        ..Default::default()
+    };
+    let (mut app, mut acc, mut tx, mut rx, mut net) = (
+        param(Some(ZRuntime::Net)),
+        param(None),
+        param(None),
+        param(Some(ZRuntime::Net)),
+        param(None),
+    );
+    RuntimeParam::unify(
+        &mut [
+            (ZRuntime::Application, &mut app),
+            (ZRuntime::Acceptor, &mut acc),
+            (ZRuntime::TX, &mut tx),
+            (ZRuntime::RX, &mut rx),
+            (ZRuntime::Net, &mut net),
+        ],
+        ZRuntime::Application,
+    );
+    // The configured handovers are overridden
+    assert_eq!(app.handover, None);
+    for p in [acc, tx, rx, net] {
+        assert_eq!(p.handover, Some(ZRuntime::Application));
+    }
+}
+
+#[test]
+fn cap_worker_threads_test() {
+    use crate::ZRuntime;
+    let param = |worker_threads, handover| RuntimeParam {
+        worker_threads,
+        handover,
+        ..Default::default()
+    };
+    let (mut app, mut acc, mut tx, mut rx, mut net) = (
+        param(4, None),
+        param(2, None),
+        param(2, None),
+        param(8, None),
+        param(16, Some(ZRuntime::Application)),
+    );
+    RuntimeParam::cap_worker_threads(&mut [&mut app, &mut acc, &mut tx, &mut rx, &mut net], 8);
+    // The handed over runtime is not accounted nor scaled
+    assert_eq!(
+        [
+            app.worker_threads,
+            acc.worker_threads,
+            tx.worker_threads,
+            rx.worker_threads
+        ],
+        [2, 1, 1, 4]
+    );
+    assert_eq!(net.worker_threads, 16);
+
+    // Each runtime keeps at least one worker thread
+    let (mut a, mut b) = (param(1, None), param(9, None));
+    RuntimeParam::cap_worker_threads(&mut [&mut a, &mut b], 5);
+    assert_eq!([a.worker_threads, b.worker_threads], [1, 4]);
+
+    // The configuration is kept below the cap
+    let (mut a, mut b) = (param(2, None), param(3, None));
+    RuntimeParam::cap_worker_threads(&mut [&mut a, &mut b], 5);
+    assert_eq!([a.worker_threads, b.worker_threads], [2, 3]);
+}
@@ -0,0 +1,58 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[macro_use]
+extern crate criterion;
+
+use criterion::{BatchSize, Criterion};
+use zenoh_shm::SharedMemoryManager;
+
+const SHM_SIZE: usize = 4 * 1_024 * 1_024;
+const BUF_SIZE: usize = 64;
+const LIVE_BUFS: usize = 16 * 1_024;
+
+fn manager(name: &str) -> SharedMemoryManager {
+    SharedMemoryManager::make(format!("{name}_{}", std::process::id()), SHM_SIZE).unwrap()
+}
+
+// Fill the manager up to LIVE_BUFS live buffers, then reclaim them all
+fn alloc_free(shm: &mut SharedMemoryManager) {
+    let bufs: Vec<_> = (0..LIVE_BUFS)
+        .map(|_| shm.alloc(BUF_SIZE).unwrap())
+        .collect();
+    drop(bufs);
+    shm.garbage_collect();
+    shm.defragment();
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    // A fresh manager per iteration, so that the busy list grows from scratch every time
+    c.bench_function("alloc/free default busy list", |b| {
+        b.iter_batched(
+            || manager("bench_busy_list_default"),
+            |mut shm| alloc_free(&mut shm),
+            BatchSize::PerIteration,
+        )
+    });
+
+    c.bench_function("alloc/free pre-sized busy list", |b| {
+        b.iter_batched(
+            || manager("bench_busy_list_sized").with_busy_list_capacity(LIVE_BUFS),
+            |mut shm| alloc_free(&mut shm),
+            BatchSize::PerIteration,
+        )
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -11,20 +11,32 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+use serde::{Deserialize, Serialize};
 use shared_memory::{Shmem, ShmemConf, ShmemError};
 use std::{
     any::Any,
     cmp,
     collections::{binary_heap::BinaryHeap, HashMap},
     fmt, mem,
+    num::NonZeroUsize,
     sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+use zenoh_buffers::{
+    writer::{BacktrackableWriter, DidntWrite, Writer},
+    ZSliceBuffer,
 };
-use zenoh_buffers::ZSliceBuffer;
 use zenoh_result::{zerror, ShmError, ZResult};
 
 const MIN_FREE_CHUNK_SIZE: usize = 1_024;
 const ACCOUNTED_OVERHEAD: usize = 4_096;
 const ZENOH_SHM_PREFIX: &str = "zenoh_shm_zid";
+// Number of times attaching to a segment not found yet is retried, and the initial delay between
+// two attempts, doubled after each attempt
+const ATTACH_RETRIES: usize = 3;
+const ATTACH_BACKOFF: Duration = Duration::from_micros(100);
+// Interval at which the reference count is polled when waiting for a chunk to be released
+const FREE_POLL_INTERVAL: Duration = Duration::from_micros(100);
 
 // Chunk header
 type ChunkHeaderType = AtomicUsize;
@@ -42,6 +54,8 @@ struct Chunk {
     base_addr: *mut u8,
     offset: usize,
     size: usize,
+    owner: Option<u32>,
+    allocated_at: Option<Instant>,
 }
 
 impl Ord for Chunk {
@@ -167,6 +181,76 @@ impl SharedMemoryBuf {
         let bp = self.buf.load(Ordering::SeqCst);
         std::slice::from_raw_parts_mut(bp, self.len)
     }
+
+    /// Returns a new handle to the same chunk of shared memory, incrementing its reference count.
+    ///
+    /// No data is copied: writes through any of the handles are visible through all of them.
+    /// The chunk is not reclaimed by the garbage collection of its manager until all the
+    /// handles have been dropped.
+    pub fn share(&self) -> SharedMemoryBuf {
+        self.inc_ref_count();
+        let rc = self.rc_ptr.load(Ordering::SeqCst);
+        let bp = self.buf.load(Ordering::SeqCst);
+        SharedMemoryBuf {
+            rc_ptr: AtomicPtr::new(rc),
+            buf: AtomicPtr::new(bp),
+            len: self.len,
+            info: self.info.clone(),
+        }
+    }
+
+    /// Waits until this handle is the only reference to its chunk, i.e. until all the other
+    /// handles, in this process or in remote ones, have been dropped.
+    ///
+    /// This allows a producer recycling a fixed set of buffers to reuse a chunk once all the
+    /// consumers are done with it. Releases are not signalled across processes, so the
+    /// reference count of the chunk is polled, yielding to the tokio runtime in between.
+    pub async fn wait_until_free(&self) {
+        while self.ref_count() > 1 {
+            tokio::time::sleep(FREE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Same as [`SharedMemoryBuf::wait_until_free`], but gives up once `timeout` has expired.
+    /// Returns `true` if this handle is the only reference to its chunk.
+    pub async fn wait_until_free_timeout(&self, timeout: Duration) -> bool {
+        tokio::time::timeout(timeout, self.wait_until_free())
+            .await
+            .is_ok()
+    }
+
+    /// Same as [`SharedMemoryBuf::wait_until_free`], but blocks the current thread. It must not
+    /// be called from an async context.
+    pub fn wait_until_free_blocking(&self) {
+        while self.ref_count() > 1 {
+            std::thread::sleep(FREE_POLL_INTERVAL);
+        }
+    }
+
+    /// Same as [`SharedMemoryBuf::wait_until_free_timeout`], but blocks the current thread. It
+    /// must not be called from an async context.
+    pub fn wait_until_free_blocking_timeout(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while self.ref_count() > 1 {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(FREE_POLL_INTERVAL);
+        }
+        true
+    }
+
+    /// Copies the content of this buffer into a new chunk allocated from the given manager.
+    ///
+    /// The returned buffer is independent from this one: it has its own reference count and
+    /// writes to any of the two buffers are not visible through the other.
+    pub fn deep_copy(&self, manager: &mut SharedMemoryManager) -> ZResult<SharedMemoryBuf> {
+        let mut copy = manager.alloc(self.len)?;
+        let slice = unsafe { copy.as_mut_slice() };
+        slice[..self.len].copy_from_slice(self.as_slice());
+        copy.set_kind(self.get_kind());
+        Ok(copy)
+    }
 }
 
 impl Drop for SharedMemoryBuf {
@@ -175,17 +259,149 @@ impl Drop for SharedMemoryBuf {
     }
 }
 
+/// Cloning a [`SharedMemoryBuf`] shares the underlying chunk, see [`SharedMemoryBuf::share`].
+/// Use [`SharedMemoryBuf::deep_copy`] to copy the data into a new chunk instead.
 impl Clone for SharedMemoryBuf {
     fn clone(&self) -> Self {
-        self.inc_ref_count();
-        let rc = self.rc_ptr.load(Ordering::SeqCst);
-        let bp = self.buf.load(Ordering::SeqCst);
-        SharedMemoryBuf {
-            rc_ptr: AtomicPtr::new(rc),
-            buf: AtomicPtr::new(bp),
-            len: self.len,
-            info: self.info.clone(),
+        self.share()
+    }
+}
+
+/*************************************/
+/*       SHARED MEMORY WRITER        */
+/*************************************/
+/// A [`Writer`] serializing directly into shared memory, for messages whose size is not
+/// known upfront.
+///
+/// Writing starts into a chunk of an initial size. Whenever a write does not fit, the content is
+/// moved into a chunk bigger by the growth factor with [`SharedMemoryManager::realloc`], up to
+/// the max size beyond which writes fail. [`Self::finish`] returns a buffer fitting the bytes
+/// written.
+pub struct SharedMemoryBufWriter<'a> {
+    manager: &'a mut SharedMemoryManager,
+    buf: SharedMemoryBuf,
+    len: usize,
+    growth_factor: f64,
+    max_size: usize,
+}
+
+impl<'a> SharedMemoryBufWriter<'a> {
+    pub const DEFAULT_GROWTH_FACTOR: f64 = 2.0;
+
+    /// Creates a writer allocating a first chunk of `initial_size` bytes from `manager`.
+    pub fn new(manager: &'a mut SharedMemoryManager, initial_size: usize) -> ZResult<Self> {
+        let buf = manager.alloc(initial_size)?;
+        Ok(Self {
+            manager,
+            buf,
+            len: 0,
+            growth_factor: Self::DEFAULT_GROWTH_FACTOR,
+            max_size: usize::MAX,
+        })
+    }
+
+    /// Sets the factor by which the chunk grows when a write does not fit. Factors smaller
+    /// than 1 are ignored: the chunk then grows just enough for each write.
+    pub fn growth_factor(mut self, growth_factor: f64) -> Self {
+        self.growth_factor = growth_factor;
+        self
+    }
+
+    /// Sets the maximum number of bytes this writer can hold, writes beyond it fail.
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a buffer holding the bytes written, moving them into a smaller chunk if the
+    /// current one is larger than needed. As for any allocation, the length of the buffer is
+    /// rounded up to the alignment of the manager.
+    pub fn finish(self) -> ZResult<SharedMemoryBuf> {
+        let fitting = align_addr_at(self.len + CHUNK_HEADER_SIZE, self.manager.alignment);
+        if fitting - CHUNK_HEADER_SIZE == self.buf.len() {
+            return Ok(self.buf);
         }
+        self.manager.realloc(&self.buf, self.len)
+    }
+
+    // Makes room for `additional` more bytes, growing the chunk if needed
+    fn reserve(&mut self, additional: usize) -> Result<(), DidntWrite> {
+        let required = self.len.checked_add(additional).ok_or(DidntWrite)?;
+        if required <= self.buf.len() {
+            return Ok(());
+        }
+        if required > self.max_size {
+            return Err(DidntWrite);
+        }
+        let grown = (self.buf.len() as f64 * self.growth_factor) as usize;
+        let new_len = cmp::min(cmp::max(grown, required), self.max_size);
+        tracing::trace!(
+            "SharedMemoryBufWriter growing from {} to {} bytes",
+            self.buf.len(),
+            new_len
+        );
+        // The current chunk is kept if the allocation fails
+        self.buf = self
+            .manager
+            .realloc(&self.buf, new_len)
+            .map_err(|_| DidntWrite)?;
+        Ok(())
+    }
+
+    fn as_writable_slice(&mut self) -> &mut [u8] {
+        let len = self.len;
+        unsafe { &mut self.buf.as_mut_slice()[len..] }
+    }
+}
+
+impl Writer for SharedMemoryBufWriter<'_> {
+    fn write(&mut self, bytes: &[u8]) -> Result<NonZeroUsize, DidntWrite> {
+        let len = NonZeroUsize::new(bytes.len()).ok_or(DidntWrite)?;
+        self.write_exact(bytes)?;
+        Ok(len)
+    }
+
+    fn write_exact(&mut self, bytes: &[u8]) -> Result<(), DidntWrite> {
+        self.reserve(bytes.len())?;
+        self.as_writable_slice()[..bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+        Ok(())
+    }
+
+    fn remaining(&self) -> usize {
+        self.max_size.saturating_sub(self.len)
+    }
+
+    fn with_slot<F>(&mut self, len: usize, f: F) -> Result<NonZeroUsize, DidntWrite>
+    where
+        F: FnOnce(&mut [u8]) -> usize,
+    {
+        self.reserve(len)?;
+        let written = f(&mut self.as_writable_slice()[..len]);
+        self.len += written;
+        NonZeroUsize::new(written).ok_or(DidntWrite)
+    }
+}
+
+impl BacktrackableWriter for SharedMemoryBufWriter<'_> {
+    type Mark = usize;
+
+    fn mark(&mut self) -> Self::Mark {
+        self.len
+    }
+
+    fn rewind(&mut self, mark: Self::Mark) -> bool {
+        self.len = mark;
+        true
     }
 }
 
@@ -194,6 +410,8 @@ impl Clone for SharedMemoryBuf {
 /*************************************/
 pub struct SharedMemoryReader {
     segments: HashMap<String, Shmem>,
+    attach_retries: usize,
+    attach_backoff: Duration,
 }
 
 unsafe impl Send for SharedMemoryReader {}
@@ -203,23 +421,62 @@ impl SharedMemoryReader {
     pub fn new() -> Self {
         Self {
             segments: HashMap::new(),
+            attach_retries: ATTACH_RETRIES,
+            attach_backoff: ATTACH_BACKOFF,
         }
     }
 
+    /// Sets how many times attaching to a segment which can not be found is retried, and the
+    /// delay before the first retry, doubled after each retry.
+    ///
+    /// A buffer may be received right before the segment it points to is fully created by its
+    /// producer, so a missing segment is only reported once the retries are exhausted. Retrying
+    /// blocks the calling thread. By default, a segment is attached in at most 3 retries within
+    /// less than a millisecond.
+    pub fn with_attach_retries(mut self, retries: usize, backoff: Duration) -> Self {
+        self.attach_retries = retries;
+        self.attach_backoff = backoff;
+        self
+    }
+
     pub fn connect_map_to_shm(&mut self, info: &SharedMemoryBufInfo) -> ZResult<()> {
-        match ShmemConf::new().flink(&info.shm_manager).open() {
-            Ok(shm) => {
-                self.segments.insert(info.shm_manager.clone(), shm);
-                Ok(())
-            }
-            Err(e) => {
-                let e = zerror!(
-                    "Unable to bind shared memory segment {}: {:?}",
-                    info.shm_manager,
-                    e
-                );
-                tracing::trace!("{}", e);
-                Err(ShmError(e).into())
+        let mut backoff = self.attach_backoff;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match ShmemConf::new().flink(&info.shm_manager).open() {
+                Ok(shm) => {
+                    self.segments.insert(info.shm_manager.clone(), shm);
+                    return Ok(());
+                }
+                Err(e) if is_attach_transient(&e) && attempts <= self.attach_retries => {
+                    tracing::trace!(
+                        "Shared memory segment {} not available yet: {:?}. Retrying in {:?}",
+                        info.shm_manager,
+                        e,
+                        backoff
+                    );
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
+                }
+                Err(e) => {
+                    let e = if attempts > 1 {
+                        zerror!(
+                            "Unable to bind shared memory segment {} after {} attempts: {:?}",
+                            info.shm_manager,
+                            attempts,
+                            e
+                        )
+                    } else {
+                        zerror!(
+                            "Unable to bind shared memory segment {}: {:?}",
+                            info.shm_manager,
+                            e
+                        )
+                    };
+                    tracing::trace!("{}", e);
+                    return Err(ShmError(e).into());
+                }
             }
         }
     }
@@ -229,6 +486,18 @@ impl SharedMemoryReader {
         // that the sender of this buffer has incremented for us.
         match self.segments.get(&info.shm_manager) {
             Some(shm) => {
+                // The info comes from a remote, never point outside of the segment
+                let end = info.offset.checked_add(info.length);
+                if info.length < CHUNK_HEADER_SIZE || end.map_or(true, |end| end > shm.len()) {
+                    let e = zerror!(
+                        "Invalid shared memory buffer in segment {}: offset {}, length {}",
+                        info.shm_manager,
+                        info.offset,
+                        info.length
+                    );
+                    tracing::trace!("{}", e);
+                    return Err(ShmError(e).into());
+                }
                 let base_ptr = shm.as_ptr();
                 let rc = unsafe { base_ptr.add(info.offset) as *mut ChunkHeaderType };
                 let rc_ptr = AtomicPtr::<ChunkHeaderType>::new(rc);
@@ -259,6 +528,19 @@ impl SharedMemoryReader {
     }
 }
 
+// Whether attaching to a segment failed because it is still being created, i.e. its link file
+// or its mapping does not exist or is not complete yet
+fn is_attach_transient(e: &ShmemError) -> bool {
+    matches!(
+        e,
+        ShmemError::LinkDoesNotExist
+            | ShmemError::LinkOpenFailed(_)
+            | ShmemError::LinkReadFailed(_)
+            | ShmemError::FlinkInvalidOsId
+            | ShmemError::MapOpenFailed(_)
+    )
+}
+
 impl Default for SharedMemoryReader {
     fn default() -> Self {
         Self::new()
@@ -272,6 +554,49 @@ impl fmt::Debug for SharedMemoryReader {
     }
 }
 
+struct LowMemoryCallback {
+    threshold: usize,
+    callback: Box<dyn FnMut(usize) + Send>,
+    armed: bool,
+}
+
+/// A chunk of a shared memory segment, as reported by [`SharedMemoryManager::dump`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkDump {
+    /// The index of the beginning of the chunk in the shm segment.
+    pub offset: usize,
+    /// The size of the chunk, including its header.
+    pub size: usize,
+    /// The reference count of the chunk, always 0 for a free chunk.
+    pub ref_count: usize,
+    /// The owner tag the chunk has been allocated with, if any.
+    pub owner: Option<u32>,
+}
+
+/// A snapshot of the state of a [`SharedMemoryManager`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedMemoryManagerDump {
+    /// The path of the shm segment.
+    pub segment_path: String,
+    /// The size of the shm segment.
+    pub size: usize,
+    /// The amount of memory available for allocation.
+    pub available: usize,
+    /// The amount of memory currently allocated.
+    pub used: usize,
+    /// The highest amount of memory allocated at once.
+    pub peak_used: usize,
+    /// The number of successful allocations.
+    pub allocations: u64,
+    /// The number of failed allocations.
+    pub failed_allocations: u64,
+    /// The chunks available for allocation, sorted by offset.
+    pub free_chunks: Vec<ChunkDump>,
+    /// The allocated chunks, sorted by offset. Their memory is reclaimed by the garbage collector
+    /// once their reference count drops to 0.
+    pub busy_chunks: Vec<ChunkDump>,
+}
+
 /// A shared memory segment manager.
 ///
 /// Allows to access a shared memory segment and reserve some parts of this segment for writing.
@@ -283,6 +608,11 @@ pub struct SharedMemoryManager {
     free_list: BinaryHeap<Chunk>,
     busy_list: Vec<Chunk>,
     alignment: usize,
+    low_memory: Option<LowMemoryCallback>,
+    out_of_memory: Option<Box<dyn FnMut(usize) + Send>>,
+    peak_used: usize,
+    allocations: u64,
+    failed_allocations: u64,
 }
 
 unsafe impl Send for SharedMemoryManager {}
@@ -323,6 +653,8 @@ impl SharedMemoryManager {
             base_addr: base_ptr,
             offset: 0,
             size: real_size,
+            owner: None,
+            allocated_at: None,
         };
         free_list.push(chunk);
         let busy_list = vec![];
@@ -334,6 +666,11 @@ impl SharedMemoryManager {
             free_list,
             busy_list,
             alignment: mem::align_of::<ChunkHeaderType>(),
+            low_memory: None,
+            out_of_memory: None,
+            peak_used: 0,
+            allocations: 0,
+            failed_allocations: 0,
         };
         tracing::trace!(
             "Created SharedMemoryManager for {:?}",
@@ -342,6 +679,17 @@ impl SharedMemoryManager {
         Ok(shm)
     }
 
+    /// Reserves room upfront for `capacity` simultaneously allocated buffers.
+    ///
+    /// This is only a hint avoiding the reallocations of the internal bookkeeping while the
+    /// number of live buffers grows, e.g. for workloads with a known steady state. It does not
+    /// limit the number of allocations nor change the behavior of the manager in any way.
+    pub fn with_busy_list_capacity(mut self, capacity: usize) -> Self {
+        self.busy_list
+            .reserve(capacity.saturating_sub(self.busy_list.len()));
+        self
+    }
+
     fn free_chunk_map_to_shmbuf(&self, chunk: &Chunk) -> SharedMemoryBuf {
         let info = SharedMemoryBufInfo {
             offset: chunk.offset,
@@ -361,7 +709,73 @@ impl SharedMemoryManager {
     }
 
     pub fn alloc(&mut self, len: usize) -> ZResult<SharedMemoryBuf> {
-        tracing::trace!("SharedMemoryManager::alloc({})", len);
+        self.alloc_chunk(len, None)
+    }
+
+    /// Allocates a buffer tagged with an opaque `owner` id.
+    ///
+    /// The tag is only kept locally by this manager and is never sent over the wire.
+    /// It allows to account the memory used by each owner with [`Self::owner_usage`]
+    /// and to reclaim it in bulk with [`Self::force_free_owner`].
+    pub fn alloc_with_owner(&mut self, len: usize, owner: u32) -> ZResult<SharedMemoryBuf> {
+        self.alloc_chunk(len, Some(owner))
+    }
+
+    /// Allocates `count` buffers of `len` bytes at once, e.g. to fill a ring of buffers upfront.
+    ///
+    /// Either all the buffers are allocated or none: if an allocation fails, the buffers already
+    /// allocated are freed right away, without waiting for the next garbage collection.
+    pub fn alloc_many(&mut self, len: usize, count: usize) -> ZResult<Vec<SharedMemoryBuf>> {
+        self.busy_list.reserve(count);
+        let mut bufs = Vec::with_capacity(count);
+        for _ in 0..count {
+            match self.alloc_chunk(len, None) {
+                Ok(buf) => bufs.push(buf),
+                Err(e) => {
+                    let offsets: Vec<usize> = bufs.iter().map(|b| b.info.offset).collect();
+                    drop(bufs);
+                    let mut freed = 0;
+                    let mut allocated = vec![];
+                    self.busy_list.retain(|c| {
+                        let is_allocated = offsets.contains(&c.offset);
+                        if is_allocated {
+                            allocated.push(*c);
+                        }
+                        !is_allocated
+                    });
+                    for mut f in allocated {
+                        freed += f.size;
+                        f.allocated_at = None;
+                        self.free_list.push(f);
+                    }
+                    self.available += freed;
+                    self.check_low_memory();
+                    return Err(e);
+                }
+            }
+        }
+        Ok(bufs)
+    }
+
+    fn alloc_chunk(&mut self, len: usize, owner: Option<u32>) -> ZResult<SharedMemoryBuf> {
+        let res = self.try_alloc_chunk(len, owner);
+        match res {
+            Ok(_) => {
+                self.allocations += 1;
+                self.peak_used = self.peak_used.max(self.used());
+            }
+            Err(_) => {
+                self.failed_allocations += 1;
+                if let Some(callback) = self.out_of_memory.as_mut() {
+                    callback(len);
+                }
+            }
+        }
+        res
+    }
+
+    fn try_alloc_chunk(&mut self, len: usize, owner: Option<u32>) -> ZResult<SharedMemoryBuf> {
+        tracing::trace!("SharedMemoryManager::alloc({}, {:?})", len, owner);
         // Always allocate a size that will keep the proper alignment requirements
         let required_len = align_addr_at(len + CHUNK_HEADER_SIZE, self.alignment);
         if self.available < required_len {
@@ -380,6 +794,8 @@ impl SharedMemoryManager {
                             base_addr: unsafe { chunk.base_addr.add(required_len) },
                             offset: chunk.offset + required_len,
                             size: chunk.size - required_len,
+                            owner: None,
+                            allocated_at: None,
                         };
                         tracing::trace!(
                             "The allocation will leave a Free Chunk: {:?}",
@@ -388,10 +804,13 @@ impl SharedMemoryManager {
                         self.free_list.push(free_chunk);
                     }
                     chunk.size = required_len;
+                    chunk.owner = owner;
+                    chunk.allocated_at = Some(Instant::now());
                     let shm_buf = self.free_chunk_map_to_shmbuf(&chunk);
                     tracing::trace!("The allocated Chunk is ({:?})", &chunk);
                     tracing::trace!("Allocated Shared Memory Buffer: {:?}", &shm_buf);
                     self.busy_list.push(chunk);
+                    self.check_low_memory();
                     Ok(shm_buf)
                 }
                 Some(c) => {
@@ -412,6 +831,20 @@ impl SharedMemoryManager {
         }
     }
 
+    /// Copies the content of `buf` into a new chunk of `len` bytes, truncating it if `len` is
+    /// smaller than the length of `buf`, and preserving its kind.
+    ///
+    /// `buf` is left untouched: once dropped, its chunk is reclaimed by the next garbage
+    /// collection as usual.
+    pub fn realloc(&mut self, buf: &SharedMemoryBuf, len: usize) -> ZResult<SharedMemoryBuf> {
+        let mut new = self.alloc(len)?;
+        let n = cmp::min(len, buf.len());
+        let slice = unsafe { new.as_mut_slice() };
+        slice[..n].copy_from_slice(&buf.as_slice()[..n]);
+        new.set_kind(buf.get_kind());
+        Ok(new)
+    }
+
     fn is_free_chunk(chunk: &Chunk) -> bool {
         let rc_ptr = chunk.base_addr as *mut ChunkHeaderType;
         let rc = unsafe { (*rc_ptr).load(Ordering::SeqCst) };
@@ -425,6 +858,8 @@ impl SharedMemoryManager {
                 base_addr: a.base_addr,
                 size: a.size + b.size,
                 offset: a.offset,
+                owner: None,
+                allocated_at: None,
             })
         } else {
             None
@@ -471,20 +906,180 @@ impl SharedMemoryManager {
         tracing::trace!("Running Garbage Collector");
 
         let mut freed = 0;
-        let (free, busy) = self
-            .busy_list
-            .iter()
-            .partition(|&c| SharedMemoryManager::is_free_chunk(c));
-        self.busy_list = busy;
+        // Filter the busy list in place so that its capacity is preserved
+        let mut free = vec![];
+        self.busy_list.retain(|c| {
+            let is_free = SharedMemoryManager::is_free_chunk(c);
+            if is_free {
+                free.push(*c);
+            }
+            !is_free
+        });
 
-        for f in free {
+        for mut f in free {
             freed += f.size;
             tracing::trace!("Garbage Collecting Chunk: {:?}", f);
+            f.owner = None;
+            f.allocated_at = None;
             self.free_list.push(f)
         }
         self.available += freed;
+        self.check_low_memory();
         freed
     }
+
+    /// Returns the amount of memory currently allocated with the given `owner` tag
+    pub fn owner_usage(&self, owner: u32) -> usize {
+        self.busy_list
+            .iter()
+            .filter(|c| c.owner == Some(owner))
+            .map(|c| c.size)
+            .sum()
+    }
+
+    /// Frees all the chunks allocated with the given `owner` tag, regardless of their
+    /// reference count, and returns the amount of memory freed.
+    ///
+    /// # Safety
+    /// The reclaimed chunks are handed out again by subsequent allocations. The caller must
+    /// guarantee that no [`SharedMemoryBuf`] pointing to them, in this or any other process,
+    /// is still accessed or dropped afterwards, e.g. because the owner has been torn down.
+    pub unsafe fn force_free_owner(&mut self, owner: u32) -> usize {
+        tracing::trace!("Force freeing chunks of owner {}", owner);
+
+        let mut freed = 0;
+        let mut owned = vec![];
+        self.busy_list.retain(|c| {
+            let is_owned = c.owner == Some(owner);
+            if is_owned {
+                owned.push(*c);
+            }
+            !is_owned
+        });
+
+        for mut f in owned {
+            freed += f.size;
+            tracing::trace!("Force freeing Chunk: {:?}", f);
+            f.owner = None;
+            f.allocated_at = None;
+            self.free_list.push(f)
+        }
+        self.available += freed;
+        self.check_low_memory();
+        freed
+    }
+
+    /// Returns the amount of memory currently available for allocation
+    pub fn available(&self) -> usize {
+        self.available
+    }
+
+    fn used(&self) -> usize {
+        self.size + ACCOUNTED_OVERHEAD - self.available
+    }
+
+    /// Returns the chunks still referenced more than `older_than` after their allocation, sorted
+    /// by offset, e.g. to find the buffers forgotten by an application in a long-running process.
+    pub fn leaked_chunks(&self, older_than: Duration) -> Vec<ChunkDump> {
+        let mut leaked: Vec<ChunkDump> = self
+            .busy_list
+            .iter()
+            .filter(|c| c.allocated_at.is_some_and(|t| t.elapsed() > older_than))
+            .filter_map(|c| {
+                let rc_ptr = c.base_addr as *mut ChunkHeaderType;
+                let ref_count = unsafe { (*rc_ptr).load(Ordering::SeqCst) };
+                (ref_count > 0).then_some(ChunkDump {
+                    offset: c.offset,
+                    size: c.size,
+                    ref_count,
+                    owner: c.owner,
+                })
+            })
+            .collect();
+        leaked.sort_by_key(|c| c.offset);
+        leaked
+    }
+
+    /// Returns a snapshot of the state of this manager, e.g. to be attached to a bug report.
+    pub fn dump(&self) -> SharedMemoryManagerDump {
+        let dump_chunk = |c: &Chunk, ref_count| ChunkDump {
+            offset: c.offset,
+            size: c.size,
+            ref_count,
+            owner: c.owner,
+        };
+        let mut free_chunks: Vec<ChunkDump> =
+            self.free_list.iter().map(|c| dump_chunk(c, 0)).collect();
+        free_chunks.sort_by_key(|c| c.offset);
+        let mut busy_chunks: Vec<ChunkDump> = self
+            .busy_list
+            .iter()
+            .map(|c| {
+                let rc_ptr = c.base_addr as *mut ChunkHeaderType;
+                dump_chunk(c, unsafe { (*rc_ptr).load(Ordering::SeqCst) })
+            })
+            .collect();
+        busy_chunks.sort_by_key(|c| c.offset);
+
+        SharedMemoryManagerDump {
+            segment_path: self.segment_path.clone(),
+            size: self.size,
+            available: self.available,
+            used: self.used(),
+            peak_used: self.peak_used,
+            allocations: self.allocations,
+            failed_allocations: self.failed_allocations,
+            free_chunks,
+            busy_chunks,
+        }
+    }
+
+    /// Registers a callback invoked when an allocation brings [`Self::available`] below `threshold`.
+    ///
+    /// The callback receives the available memory and fires only once: it is re-armed as soon as
+    /// the available memory gets back above the threshold, e.g. after a garbage collection.
+    /// Since it is invoked from within [`Self::alloc`], the callback can not access this manager;
+    /// it is however free to drop any [`SharedMemoryBuf`] it holds. Registering a new callback
+    /// replaces the previous one.
+    pub fn on_low_memory<F>(&mut self, threshold: usize, callback: F)
+    where
+        F: FnMut(usize) + Send + 'static,
+    {
+        self.low_memory = Some(LowMemoryCallback {
+            threshold,
+            callback: Box::new(callback),
+            armed: true,
+        });
+    }
+
+    /// Registers a callback invoked each time an allocation fails, even after a garbage collection.
+    ///
+    /// The callback receives the requested length, e.g. to log or account the failure. Since it is
+    /// invoked from within [`Self::alloc`], the callback can not access this manager; it is however
+    /// free to drop any [`SharedMemoryBuf`] it holds, the memory being reclaimed by the next
+    /// garbage collection. Registering a new callback replaces the previous one.
+    pub fn on_out_of_memory<F>(&mut self, callback: F)
+    where
+        F: FnMut(usize) + Send + 'static,
+    {
+        self.out_of_memory = Some(Box::new(callback));
+    }
+
+    fn check_low_memory(&mut self) {
+        if let Some(lm) = self.low_memory.as_mut() {
+            if self.available >= lm.threshold {
+                lm.armed = true;
+            } else if lm.armed {
+                lm.armed = false;
+                tracing::debug!(
+                    "SharedMemoryManager available memory ({}) below threshold ({})",
+                    self.available,
+                    lm.threshold
+                );
+                (lm.callback)(self.available);
+            }
+        }
+    }
 }
 
 impl fmt::Debug for SharedMemoryManager {
@@ -0,0 +1,405 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use zenoh_buffers::writer::Writer;
+use zenoh_shm::{
+    SharedMemoryBufInfo, SharedMemoryBufWriter, SharedMemoryManager, SharedMemoryReader,
+};
+
+const SHM_SIZE: usize = 64 * 1_024;
+const BUF_SIZE: usize = 1_024;
+
+fn manager(name: &str) -> SharedMemoryManager {
+    SharedMemoryManager::make(format!("{name}_{}", std::process::id()), SHM_SIZE).unwrap()
+}
+
+#[test]
+fn shm_manager_owner_force_free() {
+    const TENANT_A: u32 = 1;
+    const TENANT_B: u32 = 2;
+
+    let mut shm = manager("shm_manager_owner_force_free");
+
+    let a: Vec<_> = (0..3)
+        .map(|_| shm.alloc_with_owner(BUF_SIZE, TENANT_A).unwrap())
+        .collect();
+    let b: Vec<_> = (0..2)
+        .map(|_| shm.alloc_with_owner(BUF_SIZE, TENANT_B).unwrap())
+        .collect();
+    let untagged = shm.alloc(BUF_SIZE).unwrap();
+
+    let usage_a = shm.owner_usage(TENANT_A);
+    let usage_b = shm.owner_usage(TENANT_B);
+    assert!(usage_a >= 3 * BUF_SIZE);
+    assert!(usage_b >= 2 * BUF_SIZE);
+
+    // Tenant A is torn down while its buffers are still referenced, e.g. by a crashed process
+    a.into_iter().for_each(std::mem::forget);
+    // Regular garbage collection can not reclaim them
+    assert_eq!(shm.garbage_collect(), 0);
+    assert_eq!(shm.owner_usage(TENANT_A), usage_a);
+
+    let freed = unsafe { shm.force_free_owner(TENANT_A) };
+    assert_eq!(freed, usage_a);
+    assert_eq!(shm.owner_usage(TENANT_A), 0);
+    assert_eq!(shm.owner_usage(TENANT_B), usage_b);
+
+    // Tenant B and untagged buffers are still collected as usual
+    drop(b);
+    drop(untagged);
+    assert!(shm.garbage_collect() > usage_b);
+    assert_eq!(shm.owner_usage(TENANT_B), 0);
+}
+
+#[test]
+fn shm_manager_low_memory_callback() {
+    let mut shm = manager("shm_manager_low_memory_callback");
+
+    let threshold = shm.available() - 4 * BUF_SIZE;
+    let fired = Arc::new(AtomicUsize::new(0));
+    let c_fired = fired.clone();
+    shm.on_low_memory(threshold, move |available| {
+        assert!(available < threshold);
+        c_fired.fetch_add(1, Ordering::SeqCst);
+    });
+
+    // Going below the threshold fires the callback only once
+    let mut bufs: Vec<_> = (0..8).map(|_| shm.alloc(BUF_SIZE).unwrap()).collect();
+    assert!(shm.available() < threshold);
+    assert_eq!(fired.load(Ordering::SeqCst), 1);
+
+    // Still below the threshold after reclaiming some memory: no new notification
+    bufs.truncate(6);
+    shm.garbage_collect();
+    bufs.push(shm.alloc(BUF_SIZE).unwrap());
+    assert_eq!(fired.load(Ordering::SeqCst), 1);
+
+    // Once memory has recovered the callback fires again
+    drop(bufs);
+    shm.garbage_collect();
+    assert!(shm.available() >= threshold);
+    let _bufs: Vec<_> = (0..8).map(|_| shm.alloc(BUF_SIZE).unwrap()).collect();
+    assert_eq!(fired.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn shm_manager_out_of_memory_callback() {
+    let mut shm = manager("shm_manager_out_of_memory_callback");
+
+    let requested = Arc::new(AtomicUsize::new(0));
+    let c_requested = requested.clone();
+    shm.on_out_of_memory(move |len| {
+        c_requested.store(len, Ordering::SeqCst);
+    });
+
+    // Successful allocations do not fire the callback
+    let buf = shm.alloc(BUF_SIZE).unwrap();
+    assert_eq!(requested.load(Ordering::SeqCst), 0);
+
+    assert!(shm.alloc(2 * SHM_SIZE).is_err());
+    assert_eq!(requested.load(Ordering::SeqCst), 2 * SHM_SIZE);
+
+    // Still firing when the memory can only be reclaimed once the buffers are dropped
+    let bufs: Vec<_> = std::iter::from_fn(|| shm.alloc(BUF_SIZE).ok()).collect();
+    assert_eq!(requested.load(Ordering::SeqCst), BUF_SIZE);
+    drop(bufs);
+    drop(buf);
+    assert!(shm.alloc(BUF_SIZE).is_ok());
+}
+
+#[test]
+fn shm_manager_alloc_many() {
+    let mut shm = manager("shm_manager_alloc_many");
+    let total = shm.available();
+
+    let bufs = shm.alloc_many(BUF_SIZE, 8).unwrap();
+    assert_eq!(bufs.len(), 8);
+    assert!(bufs.iter().all(|b| b.len() == BUF_SIZE));
+    let used = total - shm.available();
+
+    // On failure the buffers already allocated are given back
+    assert!(shm.alloc_many(BUF_SIZE, SHM_SIZE / BUF_SIZE).is_err());
+    assert_eq!(total - shm.available(), used);
+
+    drop(bufs);
+    shm.garbage_collect();
+    assert_eq!(shm.available(), total);
+}
+
+#[test]
+fn shm_manager_leaked_chunks() {
+    let mut shm = manager("shm_manager_leaked_chunks");
+
+    let old = shm.alloc_with_owner(BUF_SIZE, 7).unwrap();
+    let released = shm.alloc(BUF_SIZE).unwrap();
+    std::thread::sleep(Duration::from_millis(100));
+    let recent = shm.alloc(BUF_SIZE).unwrap();
+    drop(released);
+
+    // Only the old chunks still referenced are reported, even before a garbage collection
+    let leaked = shm.leaked_chunks(Duration::from_millis(50));
+    assert_eq!(leaked.len(), 1);
+    assert_eq!(leaked[0].owner, Some(7));
+    assert_eq!(leaked[0].ref_count, 1);
+    assert_eq!(leaked[0].size, shm.owner_usage(7));
+
+    drop(old);
+    assert!(shm.leaked_chunks(Duration::from_millis(50)).is_empty());
+    drop(recent);
+}
+
+#[test]
+fn shm_manager_dump() {
+    let mut shm = manager("shm_manager_dump");
+    let total = shm.available();
+
+    let a = shm.alloc(BUF_SIZE).unwrap();
+    let b = shm.alloc_with_owner(2 * BUF_SIZE, 7).unwrap();
+    let c = b.clone();
+    assert!(shm.alloc(2 * SHM_SIZE).is_err());
+
+    let dump = shm.dump();
+    assert_eq!(dump.size, SHM_SIZE);
+    assert_eq!(dump.available + dump.used, total);
+    assert_eq!(dump.peak_used, dump.used);
+    assert_eq!(dump.allocations, 2);
+    assert_eq!(dump.failed_allocations, 1);
+    assert_eq!(dump.busy_chunks.len(), 2);
+    assert_eq!(dump.busy_chunks[0].offset, a.info.offset);
+    assert_eq!(dump.busy_chunks[0].ref_count, 1);
+    assert_eq!(dump.busy_chunks[0].owner, None);
+    assert_eq!(dump.busy_chunks[1].offset, b.info.offset);
+    assert_eq!(dump.busy_chunks[1].ref_count, 2);
+    assert_eq!(dump.busy_chunks[1].owner, Some(7));
+    let busy: usize = dump.busy_chunks.iter().map(|c| c.size).sum();
+    assert_eq!(busy, dump.used);
+    let free: usize = dump.free_chunks.iter().map(|c| c.size).sum();
+    assert_eq!(free, dump.available);
+
+    // Peak usage is kept once the memory has been reclaimed
+    let peak = dump.peak_used;
+    drop((a, b, c));
+    shm.garbage_collect();
+    let dump = shm.dump();
+    assert_eq!(dump.used, 0);
+    assert_eq!(dump.peak_used, peak);
+    assert!(dump.busy_chunks.is_empty());
+
+    let json = serde_json::to_string(&dump).unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&json).unwrap()["peak_used"],
+        peak
+    );
+}
+
+#[test]
+fn shm_manager_busy_list_capacity() {
+    let mut plain = manager("shm_manager_busy_list_capacity_plain");
+    let mut sized = manager("shm_manager_busy_list_capacity_sized").with_busy_list_capacity(64);
+
+    // The capacity is only a hint: both managers behave the same, even beyond it
+    for shm in [&mut plain, &mut sized] {
+        let mut bufs = vec![];
+        while let Ok(buf) = shm.alloc(BUF_SIZE / 4) {
+            bufs.push(buf);
+        }
+        assert!(bufs.len() > 64);
+        bufs.retain(|b| b.info.offset % (2 * BUF_SIZE) != 0);
+        shm.garbage_collect();
+        shm.alloc_with_owner(BUF_SIZE / 8, 7).unwrap();
+    }
+
+    let (plain, sized) = (plain.dump(), sized.dump());
+    assert_eq!(plain.available, sized.available);
+    assert_eq!(plain.allocations, sized.allocations);
+    assert_eq!(plain.failed_allocations, sized.failed_allocations);
+    assert_eq!(plain.free_chunks, sized.free_chunks);
+    assert_eq!(plain.busy_chunks, sized.busy_chunks);
+}
+
+#[test]
+fn shm_buf_share_and_deep_copy() {
+    let mut shm = manager("shm_buf_share_and_deep_copy");
+    let total = shm.available();
+
+    let mut buf = shm.alloc(BUF_SIZE).unwrap();
+    unsafe { buf.as_mut_slice() }.fill(1);
+
+    // Shares point to the same chunk and keep it alive
+    let shares: Vec<_> = (0..3).map(|_| buf.share()).collect();
+    assert_eq!(buf.ref_count(), 4);
+    assert!(shares.iter().all(|s| s.info == buf.info));
+    drop(buf);
+    assert_eq!(shm.garbage_collect(), 0);
+    let mut share = shares[0].clone();
+    unsafe { share.as_mut_slice() }
+    [0] = 2;
+    assert!(shares.iter().all(|s| s.as_slice()[0] == 2));
+    drop(share);
+    drop(shares);
+    assert!(shm.garbage_collect() > 0);
+    assert_eq!(shm.available(), total);
+
+    // A deep copy lives in its own chunk
+    let buf = shm.alloc(BUF_SIZE).unwrap();
+    let mut copy = buf.deep_copy(&mut shm).unwrap();
+    assert_ne!(copy.info.offset, buf.info.offset);
+    assert_eq!(copy.as_slice(), buf.as_slice());
+    assert_eq!((buf.ref_count(), copy.ref_count()), (1, 1));
+    unsafe { copy.as_mut_slice() }.fill(3);
+    assert!(buf.as_slice().iter().all(|b| *b != 3));
+    drop(buf);
+    shm.garbage_collect();
+    assert!(copy.as_slice().iter().all(|b| *b == 3));
+}
+
+#[test]
+fn shm_buf_wait_until_free_blocking() {
+    let mut shm = manager("shm_buf_wait_until_free_blocking");
+    let buf = shm.alloc(BUF_SIZE).unwrap();
+
+    // The only reference is free right away
+    assert!(buf.wait_until_free_blocking_timeout(Duration::ZERO));
+
+    // Times out while a consumer holds a reference
+    let consumer = buf.share();
+    assert!(!buf.wait_until_free_blocking_timeout(Duration::from_millis(10)));
+
+    // Dropping the last consumer reference wakes the waiter
+    let released = Arc::new(AtomicUsize::new(0));
+    let c_released = released.clone();
+    let handle = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        c_released.store(1, Ordering::SeqCst);
+        drop(consumer);
+    });
+    assert!(buf.wait_until_free_blocking_timeout(Duration::from_secs(5)));
+    assert_eq!(released.load(Ordering::SeqCst), 1);
+    assert_eq!(buf.ref_count(), 1);
+    handle.join().unwrap();
+
+    // The blocking variant returns once all the shares are dropped as well
+    let consumers: Vec<_> = (0..3).map(|_| buf.share()).collect();
+    let handle = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        drop(consumers);
+    });
+    buf.wait_until_free_blocking();
+    assert_eq!(buf.ref_count(), 1);
+    handle.join().unwrap();
+}
+
+#[tokio::test]
+async fn shm_buf_wait_until_free() {
+    let mut shm = manager("shm_buf_wait_until_free");
+    let buf = shm.alloc(BUF_SIZE).unwrap();
+
+    // The only reference is free right away
+    assert!(buf.wait_until_free_timeout(Duration::ZERO).await);
+
+    // Times out while a consumer holds a reference
+    let consumer = buf.share();
+    assert!(!buf.wait_until_free_timeout(Duration::from_millis(10)).await);
+
+    // The waiter yields to the runtime, letting the consumer on the same thread drop its reference
+    let task = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(consumer);
+    });
+    assert!(buf.wait_until_free_timeout(Duration::from_secs(5)).await);
+    assert_eq!(buf.ref_count(), 1);
+    task.await.unwrap();
+
+    let consumers: Vec<_> = (0..3).map(|_| buf.share()).collect();
+    let task = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        drop(consumers);
+    });
+    buf.wait_until_free().await;
+    assert_eq!(buf.ref_count(), 1);
+    task.await.unwrap();
+}
+
+#[test]
+fn shm_buf_writer_grows() {
+    let mut shm = manager("shm_buf_writer_grows");
+
+    // A sequence of records larger than the initial chunk
+    let records: Vec<(u64, String)> = (0..100u64).map(|i| (i, format!("record {i}"))).collect();
+    let mut writer = SharedMemoryBufWriter::new(&mut shm, 64)
+        .unwrap()
+        .growth_factor(1.5)
+        .max_size(8 * BUF_SIZE);
+    for (id, name) in records.iter() {
+        writer.write_exact(&id.to_le_bytes()).unwrap();
+        writer.write_u8(name.len() as u8).unwrap();
+        writer.write_exact(name.as_bytes()).unwrap();
+    }
+    let len = writer.len();
+    assert!(len > 64);
+    let buf = writer.finish().unwrap();
+    assert!(buf.len() >= len && buf.len() < len + 8);
+
+    let mut bytes = &buf.as_slice()[..len];
+    for (id, name) in records.iter() {
+        let (head, tail) = bytes.split_at(8);
+        assert_eq!(u64::from_le_bytes(head.try_into().unwrap()), *id);
+        let (n, tail) = tail.split_at(1);
+        let (s, tail) = tail.split_at(n[0] as usize);
+        assert_eq!(std::str::from_utf8(s).unwrap(), name);
+        bytes = tail;
+    }
+    assert!(bytes.is_empty());
+
+    // Writes beyond the max size fail without losing what has been written
+    let mut writer = SharedMemoryBufWriter::new(&mut shm, 64)
+        .unwrap()
+        .max_size(BUF_SIZE);
+    writer.write_exact(&[1; BUF_SIZE - 1]).unwrap();
+    assert_eq!(writer.remaining(), 1);
+    assert!(writer.write_exact(&[2; 2]).is_err());
+    writer.write_u8(2).unwrap();
+    let buf = writer.finish().unwrap();
+    assert_eq!(&buf.as_slice()[BUF_SIZE - 2..BUF_SIZE], &[1, 2]);
+}
+
+#[test]
+fn shm_reader_attach_retries() {
+    let id = format!("shm_reader_attach_retries_{}", std::process::id());
+    let path = SharedMemoryManager::make(id.clone(), SHM_SIZE)
+        .unwrap()
+        .dump()
+        .segment_path;
+    let info = SharedMemoryBufInfo::new(0, 0, path, 0);
+
+    // A segment still missing once the retries are exhausted is reported as such
+    let mut reader = SharedMemoryReader::new().with_attach_retries(2, Duration::from_millis(1));
+    let e = reader.connect_map_to_shm(&info).unwrap_err();
+    assert!(e.to_string().contains("after 3 attempts"), "{e}");
+
+    // A segment created while retrying is attached
+    let producer = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+        SharedMemoryManager::make(id, SHM_SIZE).unwrap()
+    });
+    let mut reader = SharedMemoryReader::new().with_attach_retries(10, Duration::from_millis(1));
+    reader.connect_map_to_shm(&info).unwrap();
+    drop(producer.join().unwrap());
+}
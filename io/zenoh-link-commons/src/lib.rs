@@ -39,6 +39,15 @@ use zenoh_result::ZResult;
 /*************************************/
 
 pub const BIND_INTERFACE: &str = "iface";
+// Restrict the addresses advertised for a listener bound on an unspecified address (i.e. 0.0.0.0 or [::]).
+// The values are separated by '|', e.g. `advertise_iface_deny=docker0|virbr0`.
+// Only advertise the addresses of these interfaces.
+pub const ADVERTISE_IFACE_ALLOW: &str = "advertise_iface_allow";
+// Do not advertise the addresses of these interfaces.
+pub const ADVERTISE_IFACE_DENY: &str = "advertise_iface_deny";
+// Do not advertise addresses of these kinds, among `loopback`, `link_local` and `private`.
+// Any other kind is rejected when the listener is created.
+pub const ADVERTISE_EXCLUDE: &str = "advertise_exclude";
 
 #[derive(Clone, Debug, Serialize, Hash, PartialEq, Eq)]
 pub struct Link {
@@ -19,10 +19,10 @@ use std::sync::{Arc, RwLock};
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use zenoh_core::{zread, zwrite};
-use zenoh_protocol::core::{EndPoint, Locator};
-use zenoh_result::{zerror, ZResult};
+use zenoh_protocol::core::{endpoint::Config, EndPoint, Locator};
+use zenoh_result::{bail, zerror, ZResult};
 
-use crate::BIND_INTERFACE;
+use crate::{ADVERTISE_EXCLUDE, ADVERTISE_IFACE_ALLOW, ADVERTISE_IFACE_DENY, BIND_INTERFACE};
 
 pub struct ListenerUnicastIP {
     endpoint: EndPoint,
@@ -72,6 +72,7 @@ impl ListenersUnicastIP {
     where
         F: Future<Output = ZResult<()>> + Send + 'static,
     {
+        check_advertise_config(&endpoint.config())?;
         let mut listeners = zwrite!(self.listeners);
         let c_listeners = self.listeners.clone();
         let c_addr = addr;
@@ -125,6 +126,7 @@ impl ListenersUnicastIP {
                     IpAddr::V4(_) => zenoh_util::net::get_ipv4_ipaddrs(iface),
                     IpAddr::V6(_) => zenoh_util::net::get_ipv6_ipaddrs(iface),
                 };
+                addrs.retain(|x| is_advertised(&config, x));
                 let iter = addrs.drain(..).map(|x| {
                     Locator::new(
                         value.endpoint.protocol(),
@@ -143,8 +145,171 @@ impl ListenersUnicastIP {
     }
 }
 
+// Kinds of addresses accepted by ADVERTISE_EXCLUDE
+const ADVERTISE_EXCLUDE_KINDS: [&str; 3] = ["loopback", "link_local", "private"];
+
+/// Checks the configuration restricting the advertised addresses of a listener, returning an
+/// error if [`ADVERTISE_EXCLUDE`] holds an unknown kind of addresses.
+///
+/// It is checked when a listener is added, link managers may check it beforehand to fail early.
+pub fn check_advertise_config(config: &Config) -> ZResult<()> {
+    for kind in config.values(ADVERTISE_EXCLUDE) {
+        if !ADVERTISE_EXCLUDE_KINDS.contains(&kind) {
+            bail!(
+                "Unknown {} argument: {} (expected any of {})",
+                ADVERTISE_EXCLUDE,
+                kind,
+                ADVERTISE_EXCLUDE_KINDS.join("|")
+            );
+        }
+    }
+    Ok(())
+}
+
+// Checks whether an address resulting from the expansion of an unspecified address should be advertised
+fn is_advertised(config: &Config, addr: &IpAddr) -> bool {
+    let excluded = |kind: &str| config.values(ADVERTISE_EXCLUDE).any(|v| v == kind);
+    let (is_link_local, is_private) = match addr {
+        IpAddr::V4(a) => (a.is_link_local(), a.is_private()),
+        // Unicast link-local (fe80::/10) and unique local (fc00::/7) addresses
+        IpAddr::V6(a) => (
+            (a.segments()[0] & 0xffc0) == 0xfe80,
+            (a.segments()[0] & 0xfe00) == 0xfc00,
+        ),
+    };
+    if (excluded("loopback") && addr.is_loopback())
+        || (excluded("link_local") && is_link_local)
+        || (excluded("private") && is_private)
+    {
+        return false;
+    }
+
+    let has_allow = config.values(ADVERTISE_IFACE_ALLOW).next().is_some();
+    let has_deny = config.values(ADVERTISE_IFACE_DENY).next().is_some();
+    if !has_allow && !has_deny {
+        return true;
+    }
+    let ifaces = zenoh_util::net::get_interface_names_by_addr(*addr).unwrap_or_default();
+    let matches = |key: &str| {
+        config
+            .values(key)
+            .any(|v| ifaces.iter().any(|iface| iface == v))
+    };
+    (!has_allow || matches(ADVERTISE_IFACE_ALLOW)) && !(has_deny && matches(ADVERTISE_IFACE_DENY))
+}
+
 impl Default for ListenersUnicastIP {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(config: &[(&str, &str)]) -> EndPoint {
+        let mut endpoint: EndPoint = "tcp/0.0.0.0:0".parse().unwrap();
+        endpoint
+            .config_mut()
+            .extend(
+                config
+                    .iter()
+                    .map(|(k, v)| ((*k).to_owned(), (*v).to_owned())),
+            )
+            .unwrap();
+        endpoint
+    }
+
+    // The locators advertised by a listener bound on 0.0.0.0 with the given config
+    async fn advertised(config: &[(&str, &str)]) -> Vec<IpAddr> {
+        let listeners = ListenersUnicastIP::new();
+        let token = listeners.token.child_token();
+        let c_token = token.clone();
+        listeners
+            .add_listener(
+                endpoint(config),
+                "0.0.0.0:0".parse().unwrap(),
+                async move {
+                    c_token.cancelled().await;
+                    Ok(())
+                },
+                token,
+            )
+            .await
+            .unwrap();
+        let addrs = listeners
+            .get_locators()
+            .iter()
+            .map(|l| l.address().as_str().parse::<SocketAddr>().unwrap().ip())
+            .collect();
+        listeners.token.cancel();
+        addrs
+    }
+
+    #[tokio::test]
+    async fn advertise_exclude() {
+        let excluded = |kinds: &str, addr: &str| {
+            let endpoint = endpoint(&[(ADVERTISE_EXCLUDE, kinds)]);
+            !is_advertised(&endpoint.config(), &addr.parse().unwrap())
+        };
+        assert!(excluded("loopback", "127.0.0.1"));
+        assert!(excluded("loopback", "::1"));
+        assert!(excluded("link_local", "169.254.1.1"));
+        assert!(excluded("link_local", "fe80::1"));
+        assert!(excluded("private", "192.168.1.1"));
+        assert!(excluded("private", "fd00::1"));
+        assert!(!excluded("loopback|link_local", "192.168.1.1"));
+        assert!(!excluded("loopback|link_local|private", "8.8.8.8"));
+
+        // All the addresses of the interfaces are advertised by default, unless excluded by kind
+        let expanded = zenoh_util::net::get_ipv4_ipaddrs(None);
+        assert_eq!(advertised(&[]).await, expanded);
+        let expected: Vec<IpAddr> = expanded
+            .iter()
+            .filter(|a| !a.is_loopback())
+            .cloned()
+            .collect();
+        assert_eq!(
+            advertised(&[(ADVERTISE_EXCLUDE, "loopback")]).await,
+            expected
+        );
+
+        // Unknown kinds are rejected
+        for kinds in ["public", "loopback|lan"] {
+            let endpoint = endpoint(&[(ADVERTISE_EXCLUDE, kinds)]);
+            assert!(check_advertise_config(&endpoint.config()).is_err());
+            let listeners = ListenersUnicastIP::new();
+            let token = listeners.token.child_token();
+            assert!(listeners
+                .add_listener(
+                    endpoint,
+                    "0.0.0.0:0".parse().unwrap(),
+                    async { Ok(()) },
+                    token
+                )
+                .await
+                .is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn advertise_iface() {
+        let expanded = zenoh_util::net::get_ipv4_ipaddrs(None);
+        let ifaces: Vec<String> = expanded
+            .iter()
+            .flat_map(|a| zenoh_util::net::get_interface_names_by_addr(*a).unwrap())
+            .collect();
+        let denied = advertised(&[(ADVERTISE_IFACE_DENY, ifaces.join("|").as_str())]).await;
+        assert!(denied.is_empty());
+        if let Some(iface) = ifaces.first() {
+            let allowed = advertised(&[(ADVERTISE_IFACE_ALLOW, iface.as_str())]).await;
+            assert!(!allowed.is_empty());
+            assert!(allowed
+                .iter()
+                .all(|a| zenoh_util::net::get_interface_names_by_addr(*a)
+                    .unwrap()
+                    .contains(iface)));
+        }
+    }
+}
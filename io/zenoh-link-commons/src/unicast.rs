@@ -18,9 +18,9 @@ use core::{
     hash::{Hash, Hasher},
     ops::Deref,
 };
-use std::net::SocketAddr;
+use std::{net::SocketAddr, time::Duration};
 use zenoh_protocol::core::{EndPoint, Locator};
-use zenoh_result::ZResult;
+use zenoh_result::{zerror, ZResult};
 
 pub type LinkManagerUnicast = Arc<dyn LinkManagerUnicastTrait>;
 #[async_trait]
@@ -52,6 +52,18 @@ pub trait LinkUnicastTrait: Send + Sync {
     async fn read(&self, buffer: &mut [u8]) -> ZResult<usize>;
     async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()>;
     async fn close(&self) -> ZResult<()>;
+    /// Close the link, giving up if the graceful close does not complete within `timeout`.
+    async fn close_timeout(&self, timeout: Duration) -> ZResult<()> {
+        tokio::time::timeout(timeout, self.close())
+            .await
+            .map_err(|_| {
+                zerror!(
+                    "Closing link {} timed out after {:?}",
+                    self.get_dst(),
+                    timeout
+                )
+            })?
+    }
 }
 
 impl Deref for LinkUnicast {
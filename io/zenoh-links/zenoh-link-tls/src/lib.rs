@@ -23,8 +23,12 @@ use zenoh_link_commons::LocatorInspector;
 use zenoh_protocol::core::Locator;
 use zenoh_result::ZResult;
 
+mod reconnect;
+#[cfg(test)]
+mod tests;
 mod unicast;
 mod utils;
+pub use reconnect::ReconnectingLink;
 pub use unicast::*;
 pub use utils::TlsConfigurator;
 
@@ -36,6 +40,9 @@ pub use utils::TlsConfigurator;
 //       2^16 - 1 bytes (i.e., 65535).
 const TLS_MAX_MTU: u16 = u16::MAX;
 pub const TLS_LOCATOR_PREFIX: &str = "tls";
+// Separator of the addresses of a listener bound on several addresses at once,
+// e.g. `tls/192.168.1.1:7447,10.0.0.1:7447`.
+pub const TLS_ADDRESS_SEPARATOR: char = ',';
 
 #[derive(Default, Clone, Copy)]
 pub struct TlsLocatorInspector;
@@ -58,9 +65,18 @@ zconfigurable! {
     // More info on the LINGER option and its dynamics can be found at:
     // https://blog.netherlabs.nl/articles/2009/01/18/the-ultimate-so_linger-page-or-why-is-my-tcp-not-reliable
     static ref TLS_LINGER_TIMEOUT: i32 = 10;
+    // Amount of time in milliseconds a graceful close (flush and shutdown) is allowed to take
+    // before the connection is forcibly reset. Default set to 10 s.
+    static ref TLS_CLOSE_TIMEOUT: u64 = 10_000;
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
     static ref TLS_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+    // Amount of time in milliseconds a connection accepted by a listener is allowed to take to
+    // complete the TLS handshake before being closed. Default set to 10 s.
+    static ref TLS_HANDSHAKE_TIMEOUT: u64 = 10_000;
+    // Maximum number of client configs kept by a manager for the endpoints it connects to,
+    // along with their resumable sessions. Once reached, the least recently used one is evicted.
+    static ref TLS_CLIENT_CONFIG_CACHE_SIZE: usize = 64;
 }
 
 pub mod config {
@@ -72,19 +88,92 @@ pub mod config {
     pub const TLS_SERVER_PRIVATE_KEY_RAW: &str = "server_private_key_raw";
     pub const TLS_SERVER_PRIVATE_KEY_BASE_64: &str = "server_private_key_base64";
 
+    // Password of an encrypted PKCS#8 server private key
+    pub const TLS_SERVER_PRIVATE_KEY_PASSWORD_FILE: &str = "server_private_key_password_file";
+    pub const TLS_SERVER_PRIVATE_KEY_PASSWORD_RAW: &str = "server_private_key_password_raw";
+
     pub const TLS_SERVER_CERTIFICATE_FILE: &str = "server_certificate_file";
     pub const TLS_SERVER_CERTIFICATE_RAW: &str = "server_certificate_raw";
     pub const TLS_SERVER_CERTIFICATE_BASE64: &str = "server_certificate_base64";
 
+    // DER encoded OCSP response stapled to the server certificate, letting the clients check its
+    // revocation status during the handshake. As OCSP responses expire, the file is loaded again
+    // every refresh interval (in milliseconds) if set, otherwise only when the listener is created.
+    pub const TLS_SERVER_OCSP_RESPONSE_FILE: &str = "server_ocsp_response_file";
+    pub const TLS_SERVER_OCSP_REFRESH_INTERVAL: &str = "server_ocsp_refresh_interval";
+
     pub const TLS_CLIENT_PRIVATE_KEY_FILE: &str = "client_private_key_file";
     pub const TLS_CLIENT_PRIVATE_KEY_RAW: &str = "client_private_key_raw";
     pub const TLS_CLIENT_PRIVATE_KEY_BASE64: &str = "client_private_key_base64";
 
+    // Password of an encrypted PKCS#8 client private key
+    pub const TLS_CLIENT_PRIVATE_KEY_PASSWORD_FILE: &str = "client_private_key_password_file";
+    pub const TLS_CLIENT_PRIVATE_KEY_PASSWORD_RAW: &str = "client_private_key_password_raw";
+
     pub const TLS_CLIENT_CERTIFICATE_FILE: &str = "client_certificate_file";
     pub const TLS_CLIENT_CERTIFICATE_RAW: &str = "client_certificate_raw";
     pub const TLS_CLIENT_CERTIFICATE_BASE64: &str = "client_certificate_base64";
 
     pub const TLS_CLIENT_AUTH: &str = "client_auth";
 
+    // PEM encoded certificate revocation lists checked by listeners against the certificates
+    // presented by clients when client authentication is enabled
+    pub const TLS_CLIENT_CRL_FILE: &str = "client_crl_file";
+    pub const TLS_CLIENT_CRL_RAW: &str = "client_crl_raw";
+    pub const TLS_CLIENT_CRL_BASE64: &str = "client_crl_base64";
+
     pub const TLS_SERVER_NAME_VERIFICATION: &str = "server_name_verification";
+
+    // TLS protocol versions allowed on the links of an endpoint, among "1.2" and "1.3",
+    // separated by '|'. When not set, TLS 1.3 is required as soon as the client is
+    // authenticated, as before.
+    pub const TLS_PROTOCOLS: &str = "protocols";
+
+    // ALPN protocols offered by outbound links and accepted by listeners, in order of preference
+    // and separated by '|', e.g. to be routed by an L7 proxy. Handshakes between peers both
+    // configuring protocols fail if they have none in common. When not set, ALPN is not used.
+    pub const TLS_ALPN: &str = "alpn";
+
+    // SHA-256 fingerprints of the peer leaf certificates allowed on the links of an endpoint,
+    // as hexadecimal strings (optionally colon-separated) separated by '|'. Links with a peer
+    // presenting another certificate, or none, are closed right after the handshake.
+    pub const TLS_PEER_FINGERPRINTS: &str = "peer_fingerprints";
+
+    // Whether the secrets of the handshakes are logged to the file named by the SSLKEYLOGFILE
+    // environment variable, e.g. to decrypt captured traffic with Wireshark. This exposes the
+    // traffic of the links to anyone able to read that file: only enable it for debugging.
+    pub const TLS_KEY_LOG: &str = "key_log";
+
+    // DNS name presented as SNI (and verified against the server certificate) on outbound
+    // links instead of the host of the endpoint address, e.g. when connecting through a gateway.
+    pub const TLS_SNI_OVERRIDE: &str = "sni_override";
+
+    // Session resumption lets a client skip the full handshake when reconnecting, at the cost
+    // of forward secrecy: a leaked ticket key or cached session exposes the traffic of the
+    // resumed sessions. When not set, session resumption is disabled.
+    pub const TLS_SESSION_RESUMPTION: &str = "session_resumption";
+    pub const TLS_SESSION_CACHE_SIZE: &str = "session_cache_size";
+    pub const TLS_SESSION_CACHE_SIZE_DEFAULT: usize = 256;
+
+    // Amount of time in milliseconds, greater than 0, after which a link accepted by a listener
+    // is closed if it has not received any data. When not set, idle links are never closed.
+    pub const TLS_IDLE_TIMEOUT: &str = "idle_timeout";
+
+    // Maximum number of bytes written on a link but not yet acknowledged by the peer, enforced
+    // by sizing the send buffer of the underlying socket. Once reached, writes wait for the peer
+    // to acknowledge some bytes, bounding the throughput of the link to this number of bytes per
+    // round trip time. When not set, writes are only limited by the socket buffers.
+    pub const TLS_MAX_IN_FLIGHT_BYTES: &str = "max_in_flight_bytes";
+
+    // Amount of time in milliseconds a read or a write on a link may take before failing, e.g.
+    // when the peer stops sending or draining data. Upon expiry the link is left in an unknown
+    // state and must be closed. When not set or set to 0, reads and writes wait indefinitely.
+    pub const TLS_READ_TIMEOUT: &str = "read_timeout";
+    pub const TLS_WRITE_TIMEOUT: &str = "write_timeout";
+
+    // Amount of time in milliseconds to wait for the TCP connection of a new link to be
+    // established, before the TLS handshake. As for the read and write timeouts, setting it
+    // to 0 disables it: the connection then waits for the OS to give up.
+    pub const TCP_CONNECT_TIMEOUT: &str = "tcp_connect_timeout";
+    pub const TCP_CONNECT_TIMEOUT_DEFAULT: u64 = 5_000;
 }
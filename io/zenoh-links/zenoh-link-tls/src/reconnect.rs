@@ -0,0 +1,297 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use crate::{LinkManagerUnicastTls, LinkUnicastTls};
+use async_trait::async_trait;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex as AsyncMutex, Notify};
+use zenoh_config::ConnectionRetryConf;
+use zenoh_core::{zasynclock, zread, zwrite};
+use zenoh_link_commons::LinkUnicastTrait;
+use zenoh_protocol::core::{EndPoint, Locator};
+use zenoh_result::{bail, Error, ZResult};
+
+/// A TLS link that transparently re-establishes its connection to the same endpoint upon a
+/// read or write error, hiding brief outages from its user.
+///
+/// A failed operation is retried on the new connection only if it did not transfer any byte on
+/// the dropped one. Otherwise the error is returned, as resuming the operation on another
+/// connection would corrupt the stream. The data already written on the dropped connection may
+/// still be lost, so the protocol on top must tolerate it. A graceful close by the remote is not
+/// an error: the end of the stream is returned and no reconnection is attempted.
+///
+/// Reconnections are attempted according to the retry periods, until they succeed or the outage
+/// lasts longer than the maximum outage, in which case the error is returned and the link is
+/// closed. Closing the link stops any reconnection in progress.
+///
+/// Like any link, at most one task at a time is expected to write and one task at a time to
+/// read. This holds across reconnections: both directions always switch to the same connection.
+///
+/// The source and destination locators are the ones of the first connection.
+///
+/// It is meant for raw byte streams only, not to be used under a `TransportUnicast`: the TLS
+/// session is not resumed, each new connection being a new session the remote sees as a new
+/// link, and a successful `write` does not mean that the bytes are delivered, as they may be
+/// lost with the dropped connection.
+pub struct ReconnectingLink {
+    manager: Arc<LinkManagerUnicastTls>,
+    endpoint: EndPoint,
+    retry: ConnectionRetryConf,
+    max_outage: Duration,
+    // The current connection, along with the number of times it has been re-established
+    link: RwLock<(u64, Arc<LinkUnicastTls>)>,
+    src_locator: Locator,
+    dst_locator: Locator,
+    closed: AtomicBool,
+    // Wake up a reconnection waiting for the next retry upon close
+    close_notify: Notify,
+    // Make sure there are no concurrent read or writes, including on different connections
+    write_mtx: AsyncMutex<()>,
+    read_mtx: AsyncMutex<()>,
+    // Make sure that only one of the directions reconnects
+    reconnect_mtx: AsyncMutex<()>,
+}
+
+impl ReconnectingLink {
+    /// Opens a link to the given endpoint, failing if the first connection can not be established.
+    pub async fn new(
+        manager: Arc<LinkManagerUnicastTls>,
+        endpoint: EndPoint,
+        retry: ConnectionRetryConf,
+        max_outage: Duration,
+    ) -> ZResult<Self> {
+        let link = manager.connect(&endpoint).await?;
+        Ok(Self {
+            manager,
+            endpoint,
+            retry,
+            max_outage,
+            src_locator: link.get_src().clone(),
+            dst_locator: link.get_dst().clone(),
+            link: RwLock::new((0, link)),
+            closed: AtomicBool::new(false),
+            close_notify: Notify::new(),
+            write_mtx: AsyncMutex::new(()),
+            read_mtx: AsyncMutex::new(()),
+            reconnect_mtx: AsyncMutex::new(()),
+        })
+    }
+
+    /// Returns how many times the connection has been re-established.
+    pub fn reconnections(&self) -> u64 {
+        zread!(self.link).0
+    }
+
+    fn current(&self) -> (u64, Arc<LinkUnicastTls>) {
+        let guard = zread!(self.link);
+        (guard.0, guard.1.clone())
+    }
+
+    // Replace the connection identified by the given generation, unless the other direction
+    // already did it in the meantime.
+    async fn reconnect(&self, generation: u64, err: Error) -> ZResult<()> {
+        let _guard = zasynclock!(self.reconnect_mtx);
+        let (current, link) = self.current();
+        if current != generation {
+            return Ok(());
+        }
+
+        tracing::debug!(
+            "TLS link {} to {} dropped: {}. Reconnecting.",
+            link,
+            self.endpoint,
+            err
+        );
+        // Make the other direction give up on the dropped connection as well
+        link.reap();
+        drop(link);
+
+        // Registered before checking for a close, so that a close in between is not missed
+        let closed = self.close_notify.notified();
+        tokio::pin!(closed);
+        closed.as_mut().enable();
+
+        let start = Instant::now();
+        let mut period = self.retry.period();
+        loop {
+            if self.closed.load(Ordering::Acquire) {
+                return Err(err);
+            }
+            match self.manager.connect(&self.endpoint).await {
+                Ok(link) => {
+                    // Checking for a close while holding the lock guarantees that either the
+                    // close sees the new connection, or the new connection is closed here
+                    let mut guard = zwrite!(self.link);
+                    if !self.closed.load(Ordering::Acquire) {
+                        tracing::debug!("TLS link to {} re-established: {}", self.endpoint, link);
+                        *guard = (generation + 1, link);
+                        return Ok(());
+                    }
+                    drop(guard);
+                    if let Err(e) = link.close().await {
+                        tracing::trace!("Error closing TLS link {}: {}", link, e);
+                    }
+                    return Err(err);
+                }
+                Err(e) => {
+                    let elapsed = start.elapsed();
+                    if elapsed >= self.max_outage {
+                        self.closed.store(true, Ordering::Release);
+                        bail!(
+                            "Unable to re-establish the TLS link to {} within {:?}: {}",
+                            self.endpoint,
+                            self.max_outage,
+                            e
+                        );
+                    }
+                    tracing::trace!(
+                        "Unable to re-establish the TLS link to {}: {}",
+                        self.endpoint,
+                        e
+                    );
+                    let backoff = period.next_duration().min(self.max_outage - elapsed);
+                    tokio::select! {
+                        _ = tokio::time::sleep(backoff) => {}
+                        _ = closed.as_mut() => return Err(err),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl LinkUnicastTrait for ReconnectingLink {
+    async fn close(&self) -> ZResult<()> {
+        self.closed.store(true, Ordering::Release);
+        self.close_notify.notify_waiters();
+        let (_, link) = self.current();
+        link.close().await
+    }
+
+    async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
+        let _guard = zasynclock!(self.write_mtx);
+        loop {
+            let (generation, link) = self.current();
+            match link.write(buffer).await {
+                Ok(n) => return Ok(n),
+                Err(e) => self.reconnect(generation, e).await?,
+            }
+        }
+    }
+
+    async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
+        let _guard = zasynclock!(self.write_mtx);
+        // Write piecewise to know whether the connection dropped midway
+        let mut written = 0;
+        while written < buffer.len() {
+            let (generation, link) = self.current();
+            match link.write(&buffer[written..]).await {
+                Ok(0) => bail!("Connection closed by {}", link),
+                Ok(n) => written += n,
+                Err(e) if written == 0 => self.reconnect(generation, e).await?,
+                Err(e) => bail!(
+                    "TLS link {} dropped after writing {} of {} bytes: {}",
+                    link,
+                    written,
+                    buffer.len(),
+                    e
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        let _guard = zasynclock!(self.read_mtx);
+        loop {
+            let (generation, link) = self.current();
+            match link.read(buffer).await {
+                Ok(n) => return Ok(n),
+                Err(e) => self.reconnect(generation, e).await?,
+            }
+        }
+    }
+
+    async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
+        let _guard = zasynclock!(self.read_mtx);
+        // Read piecewise to know whether the connection dropped midway
+        let mut read = 0;
+        while read < buffer.len() {
+            let (generation, link) = self.current();
+            match link.read(&mut buffer[read..]).await {
+                Ok(0) => bail!("Connection closed by {}", link),
+                Ok(n) => read += n,
+                Err(e) if read == 0 => self.reconnect(generation, e).await?,
+                Err(e) => bail!(
+                    "TLS link {} dropped after reading {} of {} bytes: {}",
+                    link,
+                    read,
+                    buffer.len(),
+                    e
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn get_src(&self) -> &Locator {
+        &self.src_locator
+    }
+
+    #[inline(always)]
+    fn get_dst(&self) -> &Locator {
+        &self.dst_locator
+    }
+
+    #[inline(always)]
+    fn get_mtu(&self) -> u16 {
+        self.current().1.get_mtu()
+    }
+
+    #[inline(always)]
+    fn get_interface_names(&self) -> Vec<String> {
+        self.current().1.get_interface_names()
+    }
+
+    #[inline(always)]
+    fn is_reliable(&self) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn is_streamed(&self) -> bool {
+        true
+    }
+}
+
+impl fmt::Display for ReconnectingLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} => {}", self.src_locator, self.dst_locator)?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ReconnectingLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReconnectingTls")
+            .field("endpoint", &self.endpoint)
+            .field("src", &self.src_locator)
+            .field("dst", &self.dst_locator)
+            .finish()
+    }
+}
@@ -12,29 +12,46 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use crate::{
-    utils::{get_tls_addr, get_tls_host, get_tls_server_name, TlsClientConfig, TlsServerConfig},
-    TLS_ACCEPT_THROTTLE_TIME, TLS_DEFAULT_MTU, TLS_LINGER_TIMEOUT, TLS_LOCATOR_PREFIX,
+    config::*,
+    utils::{
+        get_tls_addr, get_tls_listen_addrs, get_tls_server_name, load_connect_timeout,
+        load_idle_timeout, load_link_timeouts, load_max_in_flight_bytes,
+        load_ocsp_refresh_interval, load_ocsp_response, load_peer_fingerprints,
+        load_server_name_override, OcspCertResolver, TlsClientConfig, TlsServerConfig,
+    },
+    TLS_ACCEPT_THROTTLE_TIME, TLS_CLIENT_CONFIG_CACHE_SIZE, TLS_CLOSE_TIMEOUT, TLS_DEFAULT_MTU,
+    TLS_HANDSHAKE_TIMEOUT, TLS_LINGER_TIMEOUT, TLS_LOCATOR_PREFIX,
 };
 
 use async_trait::async_trait;
+use futures::FutureExt;
+use rustls::{pki_types::CertificateDer, AlertDescription, ClientConfig, ServerConfig};
+use sha2::{Digest, Sha256};
+use socket2::SockRef;
 use std::cell::UnsafeCell;
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::fmt;
+use std::future::Future;
+use std::net::Shutdown;
 use std::net::SocketAddr;
-use std::sync::Arc;
-use std::time::Duration;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::Mutex as AsyncMutex;
+use tokio::sync::{watch, Mutex as AsyncMutex};
 use tokio_rustls::{TlsAcceptor, TlsConnector, TlsStream};
 use tokio_util::sync::CancellationToken;
-use zenoh_core::zasynclock;
+use zenoh_core::{zasynclock, zlock, zread, zwrite};
 use zenoh_link_commons::{
-    get_ip_interface_names, LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait,
-    ListenersUnicastIP, NewLinkChannelSender,
+    check_advertise_config, get_ip_interface_names, LinkManagerUnicastTrait, LinkUnicast,
+    LinkUnicastTrait, ListenersUnicastIP, NewLinkChannelSender,
 };
-use zenoh_protocol::core::{EndPoint, Locator};
-use zenoh_result::{zerror, ZResult};
+use zenoh_protocol::core::{endpoint::Config, EndPoint, Locator};
+use zenoh_result::{bail, zerror, Error, ZResult};
+use zenoh_runtime::panic_message;
 
 pub struct LinkUnicastTls {
     // The underlying socket as returned from the async-rustls library
@@ -55,18 +72,79 @@ pub struct LinkUnicastTls {
     // Make sure there are no concurrent read or writes
     write_mtx: AsyncMutex<()>,
     read_mtx: AsyncMutex<()>,
+    // Time of the last read, in milliseconds since the creation of the link
+    created: Instant,
+    last_read: AtomicU64,
+    // The leaf certificate presented by the peer and its SHA-256 fingerprint, if any
+    peer_certificate: Option<CertificateDer<'static>>,
+    peer_fingerprint: Option<CertificateFingerprint>,
+    // The ALPN protocol negotiated during the handshake, if any
+    negotiated_alpn: Option<Vec<u8>>,
+    // Whether the bytes in flight are capped, the writes being flushed not to leave the bytes the
+    // send buffer cannot take yet in the TLS session until the next write
+    capped: bool,
+    timeouts: LinkTimeouts,
+    // The slot taken by this link in the limit of its manager, released on drop
+    _slot: Option<LinkSlot>,
 }
 
 unsafe impl Send for LinkUnicastTls {}
 unsafe impl Sync for LinkUnicastTls {}
 
+pub const CERTIFICATE_FINGERPRINT_SIZE: usize = 32;
+
+/// The SHA-256 digest of a DER-encoded certificate.
+pub type CertificateFingerprint = [u8; CERTIFICATE_FINGERPRINT_SIZE];
+
+/// Selected fields of the `TCP_INFO` socket option of the TCP stream underlying a TLS link.
+#[derive(Clone, Copy, Debug)]
+pub struct TcpInfo {
+    /// Smoothed round trip time.
+    pub rtt: Duration,
+    /// Round trip time variance.
+    pub rttvar: Duration,
+    /// Number of retransmission timeouts not yet recovered from.
+    pub retransmits: u8,
+    /// Sending congestion window, in segments.
+    pub snd_cwnd: u32,
+}
+
+// Prefix of the Linux `struct tcp_info` (see linux/tcp.h) up to the fields exposed by TcpInfo.
+// The kernel copies at most the size provided by the caller, so a prefix is enough.
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Default)]
+struct LinuxTcpInfo {
+    _state: u8,
+    _ca_state: u8,
+    retransmits: u8,
+    _flags: [u8; 5],
+    // From tcpi_rto to tcpi_rcv_ssthresh
+    _metrics: [u32; 15],
+    rtt: u32,
+    rttvar: u32,
+    _snd_ssthresh: u32,
+    snd_cwnd: u32,
+}
+
 impl LinkUnicastTls {
-    fn new(
+    pub(crate) fn new(
         socket: TlsStream<TcpStream>,
         src_addr: SocketAddr,
         dst_addr: SocketAddr,
+        slot: Option<LinkSlot>,
+        max_in_flight_bytes: Option<usize>,
+        timeouts: LinkTimeouts,
     ) -> LinkUnicastTls {
-        let (tcp_stream, _) = socket.get_ref();
+        let (tcp_stream, state) = socket.get_ref();
+        let peer_certificate = state
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .map(|cert| cert.clone().into_owned());
+        let peer_fingerprint = peer_certificate
+            .as_ref()
+            .map(|cert| Sha256::digest(cert.as_ref()).into());
+        let negotiated_alpn = state.alpn_protocol().map(|p| p.to_vec());
         // Set the TLS nodelay option
         if let Err(err) = tcp_stream.set_nodelay(true) {
             tracing::warn!(
@@ -89,6 +167,25 @@ impl LinkUnicastTls {
             );
         }
 
+        // Cap the bytes in flight with the send buffer, the kernel holding back the writes once
+        // it is full and waking them up as soon as the peer acknowledges some bytes. Linux
+        // doubles the requested size to account for its bookkeeping overhead.
+        if let Some(max) = max_in_flight_bytes {
+            let size = if cfg!(target_os = "linux") {
+                max / 2
+            } else {
+                max
+            };
+            if let Err(err) = SockRef::from(tcp_stream).set_send_buffer_size(size) {
+                tracing::warn!(
+                    "Unable to set SNDBUF option on TLS link {} => {}: {}",
+                    src_addr,
+                    dst_addr,
+                    err
+                );
+            }
+        }
+
         // Build the Tls object
         LinkUnicastTls {
             inner: UnsafeCell::new(socket),
@@ -98,6 +195,165 @@ impl LinkUnicastTls {
             dst_locator: Locator::new(TLS_LOCATOR_PREFIX, dst_addr.to_string(), "").unwrap(),
             write_mtx: AsyncMutex::new(()),
             read_mtx: AsyncMutex::new(()),
+            created: Instant::now(),
+            last_read: AtomicU64::new(0),
+            peer_certificate,
+            peer_fingerprint,
+            negotiated_alpn,
+            capped: max_in_flight_bytes.is_some(),
+            timeouts,
+            _slot: slot,
+        }
+    }
+
+    /// Returns the DER-encoded leaf certificate presented by the peer during the handshake, or
+    /// `None` if the peer did not present any certificate, e.g. a client when client
+    /// authentication is disabled.
+    pub fn peer_certificate(&self) -> Option<&CertificateDer<'static>> {
+        self.peer_certificate.as_ref()
+    }
+
+    /// Returns the SHA-256 fingerprint of the leaf certificate presented by the peer during
+    /// the handshake, or `None` if the peer did not present any certificate.
+    pub fn peer_certificate_fingerprint(&self) -> Option<CertificateFingerprint> {
+        self.peer_fingerprint
+    }
+
+    /// Returns the ALPN protocol negotiated during the handshake, or `None` if ALPN has not been
+    /// used, i.e. one of the peers did not configure any protocol.
+    pub fn negotiated_alpn(&self) -> Option<Vec<u8>> {
+        self.negotiated_alpn.clone()
+    }
+
+    // Checks the peer certificate against the allowed fingerprints, if any
+    fn is_peer_allowed(&self, fingerprints: Option<&[CertificateFingerprint]>) -> bool {
+        match fingerprints {
+            Some(fingerprints) => self
+                .peer_fingerprint
+                .is_some_and(|fp| fingerprints.contains(&fp)),
+            None => true,
+        }
+    }
+
+    fn touch(&self) {
+        let elapsed = self.created.elapsed().as_millis() as u64;
+        self.last_read.store(elapsed, Ordering::Relaxed);
+    }
+
+    fn idle_time(&self) -> Duration {
+        self.created.elapsed().saturating_sub(Duration::from_millis(
+            self.last_read.load(Ordering::Relaxed),
+        ))
+    }
+
+    /// Reads the `TCP_INFO` socket option of the underlying TCP stream.
+    ///
+    /// This is only supported on Linux, an error is returned on other platforms.
+    #[cfg(target_os = "linux")]
+    pub fn tcp_info(&self) -> ZResult<TcpInfo> {
+        use std::os::fd::AsRawFd;
+
+        let (tcp_stream, _) = self.get_sock().get_ref();
+        let mut info = LinuxTcpInfo::default();
+        let mut len = std::mem::size_of::<LinuxTcpInfo>() as libc::socklen_t;
+        let res = unsafe {
+            libc::getsockopt(
+                tcp_stream.as_raw_fd(),
+                libc::IPPROTO_TCP,
+                libc::TCP_INFO,
+                &mut info as *mut LinuxTcpInfo as *mut libc::c_void,
+                &mut len,
+            )
+        };
+        if res != 0 {
+            bail!(
+                "Unable to read TCP_INFO on TLS link {}: {}",
+                self,
+                std::io::Error::last_os_error()
+            );
+        }
+        Ok(TcpInfo {
+            rtt: Duration::from_micros(info.rtt.into()),
+            rttvar: Duration::from_micros(info.rttvar.into()),
+            retransmits: info.retransmits,
+            snd_cwnd: info.snd_cwnd,
+        })
+    }
+
+    /// Reads the `TCP_INFO` socket option of the underlying TCP stream.
+    ///
+    /// This is only supported on Linux, an error is returned on other platforms.
+    #[cfg(not(target_os = "linux"))]
+    pub fn tcp_info(&self) -> ZResult<TcpInfo> {
+        bail!("TCP_INFO is not supported on this platform: {}", self)
+    }
+
+    /// Returns the number of bytes written on the underlying TCP stream which have not been
+    /// acknowledged by the peer yet, i.e. the occupancy of the socket send queue.
+    ///
+    /// This is only supported on Linux, an error is returned on other platforms.
+    #[cfg(target_os = "linux")]
+    pub fn in_flight_bytes(&self) -> ZResult<usize> {
+        use std::os::fd::AsRawFd;
+
+        let (tcp_stream, _) = self.get_sock().get_ref();
+        let mut outq: libc::c_int = 0;
+        let res = unsafe { libc::ioctl(tcp_stream.as_raw_fd(), libc::TIOCOUTQ, &mut outq) };
+        if res != 0 {
+            bail!(
+                "Unable to read the send queue of TLS link {}: {}",
+                self,
+                std::io::Error::last_os_error()
+            );
+        }
+        Ok(outq as usize)
+    }
+
+    /// Returns the number of bytes written on the underlying TCP stream which have not been
+    /// acknowledged by the peer yet, i.e. the occupancy of the socket send queue.
+    ///
+    /// This is only supported on Linux, an error is returned on other platforms.
+    #[cfg(not(target_os = "linux"))]
+    pub fn in_flight_bytes(&self) -> ZResult<usize> {
+        bail!(
+            "Reading the send queue is not supported on this platform: {}",
+            self
+        )
+    }
+
+    async fn flush_capped(&self) -> ZResult<()> {
+        if self.capped {
+            self.get_sock_mut().flush().await.map_err(|e| {
+                tracing::trace!("Flush error on TLS link {}: {}", self, e);
+                zerror!(e)
+            })?;
+        }
+        Ok(())
+    }
+
+    // Bound an operation on the stream by a timeout, if any. The timeout error is set as the
+    // source of the returned error for the callers to tell it apart.
+    async fn timeout<T>(
+        &self,
+        op: &str,
+        timeout: Option<Duration>,
+        f: impl Future<Output = ZResult<T>>,
+    ) -> ZResult<T> {
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, f).await.map_err(|e| {
+                tracing::trace!("{} timeout on TLS link {}", op, self);
+                zerror!("{} on TLS link {} timed out after {:?}", op, self, timeout).set_source(e)
+            })?,
+            None => f.await,
+        }
+    }
+
+    // Shut down both directions of the underlying TCP stream so that any pending read or
+    // write on the link returns, letting its owner tear it down.
+    pub(crate) fn reap(&self) {
+        let (tcp_stream, _) = self.get_sock().get_ref();
+        if let Err(err) = SockRef::from(tcp_stream).shutdown(Shutdown::Both) {
+            tracing::warn!("Unable to shutdown TLS link {}: {}", self, err);
         }
     }
 
@@ -108,54 +364,113 @@ impl LinkUnicastTls {
     fn get_sock_mut(&self) -> &mut TlsStream<TcpStream> {
         unsafe { &mut *self.inner.get() }
     }
+
+    // NOTE: Only to be used for operations on the underlying socket that do not
+    //       interfere with concurrent reads or writes (e.g. reading or setting
+    //       socket options).
+    fn get_sock(&self) -> &TlsStream<TcpStream> {
+        unsafe { &*self.inner.get() }
+    }
 }
 
 #[async_trait]
 impl LinkUnicastTrait for LinkUnicastTls {
     async fn close(&self) -> ZResult<()> {
+        self.close_timeout(Duration::from_millis(*TLS_CLOSE_TIMEOUT))
+            .await
+    }
+
+    async fn close_timeout(&self, timeout: Duration) -> ZResult<()> {
         tracing::trace!("Closing TLS link: {}", self);
-        // Flush the TLS stream
-        let _guard = zasynclock!(self.write_mtx);
-        let tls_stream = self.get_sock_mut();
-        let res = tls_stream.flush().await;
-        tracing::trace!("TLS link flush {}: {:?}", self, res);
-        // Close the underlying TCP stream
-        let (tcp_stream, _) = tls_stream.get_mut();
-        let res = tcp_stream.shutdown().await;
-        tracing::trace!("TLS link shutdown {}: {:?}", self, res);
-        res.map_err(|e| zerror!(e).into())
+        let graceful = async {
+            // Flush the TLS stream
+            let _guard = zasynclock!(self.write_mtx);
+            let tls_stream = self.get_sock_mut();
+            let res = tls_stream.flush().await;
+            tracing::trace!("TLS link flush {}: {:?}", self, res);
+            // Close the underlying TCP stream
+            let (tcp_stream, _) = tls_stream.get_mut();
+            let res = tcp_stream.shutdown().await;
+            tracing::trace!("TLS link shutdown {}: {:?}", self, res);
+            res
+        };
+
+        match tokio::time::timeout(timeout, graceful).await {
+            Ok(res) => res.map_err(|e| zerror!(e).into()),
+            Err(_) => {
+                // The peer is not draining the stream: reset the connection instead of waiting
+                // for the LINGER timeout. A zero LINGER makes the socket send a RST once closed.
+                // NOTE: the write_mtx may still be held by a stuck writer, hence only the socket
+                //       is touched here, which does not require exclusive access. Shutting it
+                //       down makes the stuck writer (and any reader) return, so that the link
+                //       gets dropped and the socket closed.
+                let (tcp_stream, _) = self.get_sock().get_ref();
+                if let Err(err) = tcp_stream.set_linger(Some(Duration::ZERO)) {
+                    tracing::warn!("Unable to reset TLS link {}: {}", self, err);
+                }
+                self.reap();
+                bail!(
+                    "Closing TLS link {} timed out after {:?}: connection reset",
+                    self,
+                    timeout
+                )
+            }
+        }
     }
 
     async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
         let _guard = zasynclock!(self.write_mtx);
-        self.get_sock_mut().write(buffer).await.map_err(|e| {
-            tracing::trace!("Write error on TLS link {}: {}", self, e);
-            zerror!(e).into()
-        })
+        let write = async {
+            let n = self.get_sock_mut().write(buffer).await.map_err(|e| {
+                tracing::trace!("Write error on TLS link {}: {}", self, e);
+                zerror!(e)
+            })?;
+            self.flush_capped().await?;
+            ZResult::Ok(n)
+        };
+        self.timeout("Write", self.timeouts.write, write).await
     }
 
     async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
         let _guard = zasynclock!(self.write_mtx);
-        self.get_sock_mut().write_all(buffer).await.map_err(|e| {
-            tracing::trace!("Write error on TLS link {}: {}", self, e);
-            zerror!(e).into()
-        })
+        let write = async {
+            self.get_sock_mut().write_all(buffer).await.map_err(|e| {
+                tracing::trace!("Write error on TLS link {}: {}", self, e);
+                zerror!(e)
+            })?;
+            self.flush_capped().await?;
+            ZResult::Ok(())
+        };
+        self.timeout("Write", self.timeouts.write, write).await
     }
 
     async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
         let _guard = zasynclock!(self.read_mtx);
-        self.get_sock_mut().read(buffer).await.map_err(|e| {
-            tracing::trace!("Read error on TLS link {}: {}", self, e);
-            zerror!(e).into()
-        })
+        let read = async {
+            let n = self.get_sock_mut().read(buffer).await.map_err(|e| {
+                tracing::trace!("Read error on TLS link {}: {}", self, e);
+                zerror!(e)
+            })?;
+            ZResult::Ok(n)
+        };
+        let n = self.timeout("Read", self.timeouts.read, read).await?;
+        if n > 0 {
+            self.touch();
+        }
+        Ok(n)
     }
 
     async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
         let _guard = zasynclock!(self.read_mtx);
-        let _ = self.get_sock_mut().read_exact(buffer).await.map_err(|e| {
-            tracing::trace!("Read error on TLS link {}: {}", self, e);
-            zerror!(e)
-        })?;
+        let read = async {
+            let _ = self.get_sock_mut().read_exact(buffer).await.map_err(|e| {
+                tracing::trace!("Read error on TLS link {}: {}", self, e);
+                zerror!(e)
+            })?;
+            ZResult::Ok(())
+        };
+        self.timeout("Read", self.timeouts.read, read).await?;
+        self.touch();
         Ok(())
     }
 
@@ -215,38 +530,514 @@ impl fmt::Debug for LinkUnicastTls {
     }
 }
 
+// The number of links open by a manager, along with the maximum allowed if any
+#[derive(Clone, Default)]
+struct LinkLimit {
+    count: Arc<AtomicUsize>,
+    max: Option<usize>,
+}
+
+impl LinkLimit {
+    // Take a slot for a new link, unless the maximum number of links has been reached
+    fn acquire(&self) -> Option<LinkSlot> {
+        self.count
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                match self.max {
+                    Some(max) if count >= max => None,
+                    _ => Some(count + 1),
+                }
+            })
+            .ok()?;
+        Some(LinkSlot {
+            count: self.count.clone(),
+            listener: None,
+        })
+    }
+}
+
+pub(crate) struct LinkSlot {
+    count: Arc<AtomicUsize>,
+    // The counters of the listener which accepted the link, if any
+    listener: Option<Arc<ListenerCounters>>,
+}
+
+impl LinkSlot {
+    // Account the link among the open links of the listener which accepted it
+    fn attach(&mut self, listener: Arc<ListenerCounters>) {
+        listener.open_links.fetch_add(1, Ordering::AcqRel);
+        self.listener = Some(listener);
+    }
+}
+
+impl Drop for LinkSlot {
+    fn drop(&mut self) {
+        self.count.fetch_sub(1, Ordering::AcqRel);
+        if let Some(listener) = self.listener.as_ref() {
+            listener.open_links.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+}
+
+/// Counters of the connections accepted by a TLS listener, see
+/// [`LinkManagerUnicastTls::listener_stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ListenerStats {
+    /// The number of links accepted.
+    pub accepted: u64,
+    /// The number of connections closed during the handshake, because it failed or the peer
+    /// certificate is not allowed.
+    pub handshake_failures: u64,
+    /// The number of accepted links currently open.
+    pub open_links: usize,
+    /// The number of errors accepting TCP connections, e.g. when the process has run out of
+    /// file descriptors.
+    pub accept_errors: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct ListenerCounters {
+    accepted: AtomicU64,
+    handshake_failures: AtomicU64,
+    open_links: AtomicUsize,
+    accept_errors: AtomicU64,
+}
+
+impl ListenerCounters {
+    fn stats(&self) -> ListenerStats {
+        ListenerStats {
+            accepted: self.accepted.load(Ordering::Relaxed),
+            handshake_failures: self.handshake_failures.load(Ordering::Relaxed),
+            open_links: self.open_links.load(Ordering::Acquire),
+            accept_errors: self.accept_errors.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// An error of a TLS listener reported to the sink set with
+/// [`LinkManagerUnicastTls::with_error_sink`].
+#[derive(Debug)]
+pub struct ListenerError {
+    /// The address of the peer whose connection failed.
+    pub peer: SocketAddr,
+    /// The error, e.g. the message of a panic during the handshake.
+    pub error: Error,
+}
+
+/// A channel the listeners of a [`LinkManagerUnicastTls`] report their errors to, see
+/// [`LinkManagerUnicastTls::with_error_sink`].
+pub type ErrorSink = flume::Sender<ListenerError>;
+
+// Settings of the links accepted by a listener, from the configuration of its endpoint
+#[derive(Clone, Default)]
+struct AcceptConfig {
+    fingerprints: Option<Arc<Vec<CertificateFingerprint>>>,
+    max_in_flight_bytes: Option<usize>,
+    timeouts: LinkTimeouts,
+    stats: Arc<ListenerCounters>,
+    idle: Option<(IdleLinks, Duration)>,
+    error_sink: Option<ErrorSink>,
+}
+
+// Timeouts of the reads and writes on a link, none meaning no timeout
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct LinkTimeouts {
+    pub(crate) read: Option<Duration>,
+    pub(crate) write: Option<Duration>,
+}
+
 pub struct LinkManagerUnicastTls {
     manager: NewLinkChannelSender,
     listeners: ListenersUnicastIP,
+    // Links open by this manager, both accepted and outbound
+    limit: LinkLimit,
+    // Client configs of the outbound links, indexed by endpoint configuration. They are built
+    // once and shared by all the links to the same endpoint, along with their resumable sessions.
+    client_configs: RwLock<ClientConfigs>,
+    // Accepted links to close once idle, swept by a single task per manager. The task is spawned
+    // along with the first listener configured with an idle timeout, and is then sent the
+    // shortest idle timeout of the listeners to adjust its period.
+    idle_links: IdleLinks,
+    idle_sweeper: Mutex<Option<watch::Sender<Duration>>>,
+    // Acceptors and counters of the listeners, indexed by bound address
+    listener_states: RwLock<HashMap<SocketAddr, ListenerState>>,
+    // Addresses bound by the listeners and their locators, indexed by the endpoint each listener
+    // has been created on, e.g. with ephemeral ports
+    listener_addrs: RwLock<HashMap<EndPoint, Vec<(SocketAddr, Locator)>>>,
+    // User hooks applied to the rustls configs once built from the endpoint configuration
+    server_config_customizer: Option<ServerConfigCustomizer>,
+    client_config_customizer: Option<ClientConfigCustomizer>,
+    error_sink: Option<ErrorSink>,
+}
+
+/// A hook modifying the rustls [`ServerConfig`] of a listener, see
+/// [`LinkManagerUnicastTls::with_server_config_customizer`].
+pub type ServerConfigCustomizer = Arc<dyn Fn(ServerConfig) -> ZResult<ServerConfig> + Send + Sync>;
+
+/// A hook modifying the rustls [`ClientConfig`] of outbound links, see
+/// [`LinkManagerUnicastTls::with_client_config_customizer`].
+pub type ClientConfigCustomizer = Arc<dyn Fn(ClientConfig) -> ZResult<ClientConfig> + Send + Sync>;
+
+// The acceptor of a listener along with what it has been built from, shared by its accept loops
+// and swapped when its config is reloaded
+#[derive(Clone)]
+pub(crate) struct ListenerAcceptor {
+    acceptor: TlsAcceptor,
+    // Presents the certificate of the acceptor, its OCSP response being refreshed in place
+    cert_resolver: Arc<OcspCertResolver>,
+    // The endpoint the acceptor has been built from, e.g. locating the OCSP response file
+    endpoint: EndPoint,
+}
+
+impl ListenerAcceptor {
+    fn new(config: TlsServerConfig, endpoint: &EndPoint) -> Self {
+        Self {
+            acceptor: TlsAcceptor::from(Arc::new(config.server_config)),
+            cert_resolver: config.cert_resolver,
+            endpoint: endpoint.clone(),
+        }
+    }
+}
+
+pub(crate) type SharedAcceptor = Arc<RwLock<ListenerAcceptor>>;
+
+// The state of a listener shared by all the addresses it is bound to
+#[derive(Clone)]
+struct ListenerState {
+    acceptor: SharedAcceptor,
+    stats: Arc<ListenerCounters>,
 }
 
+// A cache of client configs, evicting the least recently used one once full
+struct ClientConfigs {
+    capacity: usize,
+    configs: HashMap<String, (Arc<ClientConfig>, AtomicU64)>,
+    clock: AtomicU64,
+}
+
+impl ClientConfigs {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            configs: HashMap::new(),
+            clock: AtomicU64::new(0),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<Arc<ClientConfig>> {
+        let (config, last_used) = self.configs.get(key)?;
+        last_used.store(
+            self.clock.fetch_add(1, Ordering::Relaxed),
+            Ordering::Relaxed,
+        );
+        Some(config.clone())
+    }
+
+    fn insert(&mut self, key: &str, config: Arc<ClientConfig>) {
+        if !self.configs.contains_key(key) && self.configs.len() >= self.capacity {
+            let lru = self
+                .configs
+                .iter()
+                .min_by_key(|(_, (_, last_used))| last_used.load(Ordering::Relaxed))
+                .map(|(k, _)| k.clone());
+            if let Some(lru) = lru {
+                self.configs.remove(&lru);
+            }
+        }
+        let last_used = AtomicU64::new(self.clock.fetch_add(1, Ordering::Relaxed));
+        self.configs.insert(key.to_owned(), (config, last_used));
+    }
+}
+
+// Links accepted by a listener configured with an idle timeout, along with that timeout
+type IdleLinkList = Mutex<Vec<(Weak<LinkUnicastTls>, Duration)>>;
+type IdleLinks = Arc<IdleLinkList>;
+
 impl LinkManagerUnicastTls {
     pub fn new(manager: NewLinkChannelSender) -> Self {
         Self {
             manager,
             listeners: ListenersUnicastIP::new(),
+            limit: LinkLimit::default(),
+            client_configs: RwLock::new(ClientConfigs::new(*TLS_CLIENT_CONFIG_CACHE_SIZE)),
+            idle_links: IdleLinks::default(),
+            idle_sweeper: Mutex::new(None),
+            listener_states: RwLock::new(HashMap::new()),
+            listener_addrs: RwLock::new(HashMap::new()),
+            server_config_customizer: None,
+            client_config_customizer: None,
+            error_sink: None,
         }
     }
-}
 
-#[async_trait]
-impl LinkManagerUnicastTrait for LinkManagerUnicastTls {
-    async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
+    /// Limits the number of links simultaneously open by this manager, both accepted by its
+    /// listeners and opened towards other endpoints.
+    ///
+    /// Once the limit is reached, new connections are closed right after being accepted and
+    /// new links can not be opened, until some of the open links are closed.
+    pub fn with_max_links(mut self, max_links: usize) -> Self {
+        self.limit.max = Some(max_links);
+        self
+    }
+
+    /// Registers a hook customizing the rustls [`ServerConfig`] of the listeners of this manager,
+    /// e.g. to set a custom certificate resolver or client certificate verifier.
+    ///
+    /// The hook runs after the config has been built from the endpoint configuration and
+    /// before it is used by the listener, so it can override any setting made from the
+    /// configuration keys. An error returned by the hook fails the creation of the listener.
+    /// A certificate resolver replacing the one built from the configuration is not stapled the
+    /// OCSP responses loaded every `server_ocsp_refresh_interval`.
+    pub fn with_server_config_customizer<F>(mut self, customizer: F) -> Self
+    where
+        F: Fn(ServerConfig) -> ZResult<ServerConfig> + Send + Sync + 'static,
+    {
+        self.server_config_customizer = Some(Arc::new(customizer));
+        self
+    }
+
+    /// Registers a hook customizing the rustls [`ClientConfig`] of the links opened by this
+    /// manager, e.g. to set the ALPN protocols or a custom server certificate verifier.
+    ///
+    /// The hook runs after the config has been built from the endpoint configuration and
+    /// before it is used by any link, including after [`Self::reload_client_config`], so it can
+    /// override any setting made from the configuration keys. An error returned by the hook
+    /// fails the creation of the link.
+    pub fn with_client_config_customizer<F>(mut self, customizer: F) -> Self
+    where
+        F: Fn(ClientConfig) -> ZResult<ClientConfig> + Send + Sync + 'static,
+    {
+        self.client_config_customizer = Some(Arc::new(customizer));
+        self
+    }
+
+    /// Sets a channel the listeners of this manager report their errors to, along with the
+    /// address of the peer, e.g. when a TLS handshake panics.
+    ///
+    /// The errors are reported in addition to being logged. They are dropped if the channel is
+    /// full or disconnected, the listeners never waiting for it.
+    pub fn with_error_sink(mut self, sink: ErrorSink) -> Self {
+        self.error_sink = Some(sink);
+        self
+    }
+
+    // Builds the server config of a listener on the given endpoint, customized if requested
+    async fn server_config(&self, endpoint: &EndPoint) -> ZResult<TlsServerConfig> {
+        let mut config = TlsServerConfig::new(&endpoint.config()).await?;
+        if let Some(customizer) = self.server_config_customizer.as_ref() {
+            config.server_config = customizer(config.server_config)?;
+        }
+        Ok(config)
+    }
+
+    fn customize_client_config(&self, config: ClientConfig) -> ZResult<ClientConfig> {
+        match self.client_config_customizer.as_ref() {
+            Some(customizer) => customizer(config),
+            None => Ok(config),
+        }
+    }
+
+    /// Returns the number of links currently open by this manager.
+    pub fn link_count(&self) -> usize {
+        self.limit.count.load(Ordering::Acquire)
+    }
+
+    pub(crate) async fn client_config(&self, config: &Config<'_>) -> ZResult<Arc<ClientConfig>> {
+        if let Some(client_config) = zread!(self.client_configs).get(config.as_str()) {
+            return Ok(client_config);
+        }
+        let client_config = TlsClientConfig::new(config).await?.client_config;
+        let client_config = Arc::new(self.customize_client_config(client_config)?);
+        let mut client_configs = zwrite!(self.client_configs);
+        // Another link towards the same endpoint may have built it in the meantime
+        if let Some(client_config) = client_configs.get(config.as_str()) {
+            return Ok(client_config);
+        }
+        client_configs.insert(config.as_str(), client_config.clone());
+        Ok(client_config)
+    }
+
+    /// Rebuilds the client config used by new links towards the given endpoint.
+    ///
+    /// Certificates, private keys and trust anchors are loaded again from their sources, e.g.
+    /// after a certificate rotation. Links already established keep their current config.
+    /// Resumable sessions are not carried over, so new links perform a full handshake.
+    pub async fn reload_client_config(&self, endpoint: &EndPoint) -> ZResult<()> {
+        let config = endpoint.config();
+        let client_config = TlsClientConfig::new(&config)
+            .await
+            .and_then(|c| self.customize_client_config(c.client_config))
+            .map_err(|e| zerror!("Cannot reload the TLS client config for {endpoint}: {e}"))?;
+        zwrite!(self.client_configs).insert(config.as_str(), Arc::new(client_config));
+        Ok(())
+    }
+
+    /// Rebuilds the server config used by the listener on the given endpoint.
+    ///
+    /// Certificates, private keys and trust anchors are loaded again from the configuration of
+    /// `endpoint`, e.g. after a certificate rotation, and used by the handshakes started
+    /// afterwards. Links already established are unaffected. The address of `endpoint` must
+    /// resolve to the addresses bound by the listener, e.g. be its locator if it listens on an
+    /// ephemeral port.
+    pub async fn reload_server_config(&self, endpoint: &EndPoint) -> ZResult<()> {
+        let epaddr = endpoint.address();
+        let addrs = get_tls_listen_addrs(&epaddr)
+            .await
+            .map_err(|e| zerror!("Cannot reload the TLS server config for {endpoint}: {e}"))?;
+        let mut acceptors = Vec::with_capacity(addrs.len());
+        {
+            let guard = zread!(self.listener_states);
+            for (_, addr) in addrs {
+                match guard.get(&addr) {
+                    Some(state) => acceptors.push(state.acceptor.clone()),
+                    None => bail!(
+                        "Cannot reload the TLS server config for {endpoint}: no listener on {addr}"
+                    ),
+                }
+            }
+        }
+
+        let server_config = self
+            .server_config(endpoint)
+            .await
+            .map_err(|e| zerror!("Cannot reload the TLS server config for {endpoint}: {e}"))?;
+        let acceptor = ListenerAcceptor::new(server_config, endpoint);
+        for shared in acceptors {
+            *zwrite!(shared) = acceptor.clone();
+        }
+        Ok(())
+    }
+
+    /// Returns the locators of all the addresses bound by the listener on the given endpoint.
+    ///
+    /// `endpoint` is either the endpoint the listener has been created on, or one of its
+    /// locators, e.g. the one returned when creating the listener.
+    pub async fn listener_locators(&self, endpoint: &EndPoint) -> ZResult<Vec<Locator>> {
+        let key = self
+            .find_listener(endpoint)
+            .await?
+            .ok_or_else(|| zerror!("No TLS listener on {endpoint}"))?;
+        Ok(zread!(self.listener_addrs)
+            .get(&key)
+            .map(|bound| bound.iter().map(|(_, locator)| locator.clone()).collect())
+            .unwrap_or_default())
+    }
+
+    // Returns the endpoint the listener bound to the given endpoint has been created on, if any,
+    // the given endpoint being either that one or one of the locators of the listener
+    async fn find_listener(&self, endpoint: &EndPoint) -> ZResult<Option<EndPoint>> {
+        if zread!(self.listener_addrs).contains_key(endpoint) {
+            return Ok(Some(endpoint.clone()));
+        }
+        let addrs = get_tls_listen_addrs(&endpoint.address()).await?;
+        Ok(zread!(self.listener_addrs)
+            .iter()
+            .find(|(_, bound)| {
+                bound
+                    .iter()
+                    .any(|(bound, _)| addrs.iter().any(|(_, addr)| addr == bound))
+            })
+            .map(|(key, _)| key.clone()))
+    }
+
+    /// Returns the counters of the connections accepted by the listener on the given endpoint.
+    ///
+    /// The counters cover all the addresses the listener is bound to. As for
+    /// [`Self::reload_server_config`], the address of `endpoint` must resolve to the addresses
+    /// bound by the listener.
+    pub async fn listener_stats(&self, endpoint: &EndPoint) -> ZResult<ListenerStats> {
+        let epaddr = endpoint.address();
+        let addrs = get_tls_listen_addrs(&epaddr).await?;
+        let guard = zread!(self.listener_states);
+        addrs
+            .iter()
+            .find_map(|(_, addr)| guard.get(addr))
+            .map(|state| state.stats.stats())
+            .ok_or_else(|| zerror!("No TLS listener on {endpoint}").into())
+    }
+
+    /// Validates the TLS configuration of an endpoint without binding or connecting any socket.
+    ///
+    /// Certificates, private keys and trust anchors are loaded and parsed exactly as done when
+    /// opening a link. The server side configuration is validated as well if the endpoint
+    /// provides a server certificate or private key. The first error encountered is returned.
+    pub async fn validate(&self, endpoint: &EndPoint) -> ZResult<()> {
         let epaddr = endpoint.address();
         let epconf = endpoint.config();
 
-        let server_name = get_tls_server_name(&epaddr)?;
+        let map_err = |e: Error| zerror!("Invalid TLS configuration for {endpoint}: {e}");
+        get_tls_server_name(&epaddr).map_err(map_err)?;
+        load_server_name_override(&epconf).map_err(map_err)?;
+        load_peer_fingerprints(&epconf).map_err(map_err)?;
+        load_max_in_flight_bytes(&epconf).map_err(map_err)?;
+        load_link_timeouts(&epconf).map_err(map_err)?;
+        load_ocsp_refresh_interval(&epconf).map_err(map_err)?;
+        load_idle_timeout(&epconf).map_err(map_err)?;
+        check_advertise_config(&epconf).map_err(map_err)?;
+        load_connect_timeout(&epconf).map_err(map_err)?;
+        TlsClientConfig::new(&epconf).await.map_err(map_err)?;
+        if [
+            TLS_SERVER_PRIVATE_KEY_RAW,
+            TLS_SERVER_PRIVATE_KEY_FILE,
+            TLS_SERVER_PRIVATE_KEY_BASE_64,
+            TLS_SERVER_CERTIFICATE_RAW,
+            TLS_SERVER_CERTIFICATE_FILE,
+            TLS_SERVER_CERTIFICATE_BASE64,
+        ]
+        .iter()
+        .any(|k| epconf.get(k).is_some())
+        {
+            TlsServerConfig::new(&epconf).await.map_err(map_err)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn connect(&self, endpoint: &EndPoint) -> ZResult<Arc<LinkUnicastTls>> {
+        let epaddr = endpoint.address();
+        let epconf = endpoint.config();
+
+        // The SNI override (if any) replaces the host of the address, which is still connected to
+        let server_name = match load_server_name_override(&epconf)
+            .map_err(|e| zerror!("Cannot create a new TLS link to {endpoint}: {e}"))?
+        {
+            Some(server_name) => server_name,
+            None => get_tls_server_name(&epaddr)?.to_owned(),
+        };
         let addr = get_tls_addr(&epaddr).await?;
 
+        let slot = self.limit.acquire().ok_or_else(|| {
+            zerror!("Can not create a new TLS link to {endpoint}: maximum number of links reached")
+        })?;
+
         // Initialize the TLS Config
-        let client_config = TlsClientConfig::new(&epconf)
+        let config = self
+            .client_config(&epconf)
             .await
             .map_err(|e| zerror!("Cannot create a new TLS listener to {endpoint}: {e}"))?;
-        let config = Arc::new(client_config.client_config);
         let connector = TlsConnector::from(config);
+        let connect_timeout = load_connect_timeout(&epconf)
+            .map_err(|e| zerror!("Cannot create a new TLS link to {endpoint}: {e}"))?;
+        let fingerprints = load_peer_fingerprints(&epconf)
+            .map_err(|e| zerror!("Cannot create a new TLS link to {endpoint}: {e}"))?;
+        let max_in_flight_bytes = load_max_in_flight_bytes(&epconf)
+            .map_err(|e| zerror!("Cannot create a new TLS link to {endpoint}: {e}"))?;
+        let timeouts = load_link_timeouts(&epconf)
+            .map_err(|e| zerror!("Cannot create a new TLS link to {endpoint}: {e}"))?;
 
         // Initialize the TcpStream
-        let tcp_stream = TcpStream::connect(addr).await.map_err(|e| {
+        let connect = TcpStream::connect(addr);
+        let tcp_stream = match connect_timeout {
+            Some(connect_timeout) => tokio::time::timeout(connect_timeout, connect)
+                .await
+                .map_err(|_| {
+                    zerror!(
+                        "Can not create a new TLS link bound to {:?}: connect timed out after {:?}",
+                        server_name,
+                        connect_timeout
+                    )
+                })?,
+            None => connect.await,
+        }
+        .map_err(|e| {
             zerror!(
                 "Can not create a new TLS link bound to {:?}: {}",
                 server_name,
@@ -272,19 +1063,44 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTls {
 
         // Initialize the TlsStream
         let tls_stream = connector
-            .connect(server_name.to_owned(), tcp_stream)
+            .connect(server_name.clone(), tcp_stream)
             .await
             .map_err(|e| {
-                zerror!(
-                    "Can not create a new TLS link bound to {:?}: {}",
-                    server_name,
-                    e
-                )
+                if is_alpn_mismatch(&e) {
+                    zerror!(
+                        "Can not create a new TLS link bound to {:?}: no ALPN protocol in common with the server: {}",
+                        server_name,
+                        e
+                    )
+                } else {
+                    zerror!(
+                        "Can not create a new TLS link bound to {:?}: {}",
+                        server_name,
+                        e
+                    )
+                }
             })?;
         let tls_stream = TlsStream::Client(tls_stream);
 
-        let link = Arc::new(LinkUnicastTls::new(tls_stream, src_addr, dst_addr));
+        let link = LinkUnicastTls::new(
+            tls_stream,
+            src_addr,
+            dst_addr,
+            Some(slot),
+            max_in_flight_bytes,
+            timeouts,
+        );
+        if !link.is_peer_allowed(fingerprints.as_deref().map(Vec::as_slice)) {
+            bail!("Can not create a new TLS link to {endpoint}: peer certificate not allowed");
+        }
+        Ok(Arc::new(link))
+    }
+}
 
+#[async_trait]
+impl LinkManagerUnicastTrait for LinkManagerUnicastTls {
+    async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
+        let link = self.connect(&endpoint).await?;
         Ok(LinkUnicast(link))
     }
 
@@ -292,50 +1108,185 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTls {
         let epaddr = endpoint.address();
         let epconf = endpoint.config();
 
-        let addr = get_tls_addr(&epaddr).await?;
-        let host = get_tls_host(&epaddr)?;
+        // Several addresses separated by a comma can be bound by a single listener, either on all
+        // of them or on none. The locator of the first address is returned, the locators of all
+        // the addresses being returned by get_locators and listener_locators.
+        let addrs = get_tls_listen_addrs(&epaddr).await?;
 
         // Initialize TlsConfig
-        let tls_server_config = TlsServerConfig::new(&epconf)
-            .await
-            .map_err(|e| zerror!("Cannot create a new TLS listener on {addr}. {e}"))?;
-
-        // Initialize the TcpListener
-        let socket = TcpListener::bind(addr)
+        let tls_server_config = self
+            .server_config(&endpoint)
             .await
-            .map_err(|e| zerror!("Can not create a new TLS listener on {}: {}", addr, e))?;
+            .map_err(|e| zerror!("Cannot create a new TLS listener on {epaddr}. {e}"))?;
+        let idle_timeout = load_idle_timeout(&epconf)
+            .map_err(|e| zerror!("Cannot create a new TLS listener on {epaddr}. {e}"))?;
+        check_advertise_config(&epconf)
+            .map_err(|e| zerror!("Cannot create a new TLS listener on {epaddr}. {e}"))?;
+        let ocsp_refresh_interval = load_ocsp_refresh_interval(&epconf)
+            .map_err(|e| zerror!("Cannot create a new TLS listener on {epaddr}. {e}"))?;
+        let mut accept_config = AcceptConfig {
+            fingerprints: load_peer_fingerprints(&epconf)
+                .map_err(|e| zerror!("Cannot create a new TLS listener on {epaddr}. {e}"))?,
+            max_in_flight_bytes: load_max_in_flight_bytes(&epconf)
+                .map_err(|e| zerror!("Cannot create a new TLS listener on {epaddr}. {e}"))?,
+            timeouts: load_link_timeouts(&epconf)
+                .map_err(|e| zerror!("Cannot create a new TLS listener on {epaddr}. {e}"))?,
+            stats: Arc::default(),
+            idle: None,
+            error_sink: self.error_sink.clone(),
+        };
 
-        let local_addr = socket
-            .local_addr()
-            .map_err(|e| zerror!("Can not create a new TLS listener on {}: {}", addr, e))?;
-        let local_port = local_addr.port();
+        // Initialize the TcpListeners, all addresses being bound before any of them accepts
+        let mut sockets = Vec::with_capacity(addrs.len());
+        for (host, addr) in addrs {
+            let socket = TcpListener::bind(addr)
+                .await
+                .map_err(|e| zerror!("Can not create a new TLS listener on {}: {}", addr, e))?;
+            let local_addr = socket
+                .local_addr()
+                .map_err(|e| zerror!("Can not create a new TLS listener on {}: {}", addr, e))?;
+            sockets.push((host, socket, local_addr));
+        }
 
         // Initialize the TlsAcceptor
-        let acceptor = TlsAcceptor::from(Arc::new(tls_server_config.server_config));
-        let token = self.listeners.token.child_token();
-        let c_token = token.clone();
-        let c_manager = self.manager.clone();
+        let acceptor: SharedAcceptor = Arc::new(RwLock::new(ListenerAcceptor::new(
+            tls_server_config,
+            &endpoint,
+        )));
 
-        let task = async move { accept_task(socket, acceptor, c_token, c_manager).await };
+        // Track the accepted links if they need to be closed once idle
+        accept_config.idle = idle_timeout.map(|timeout| {
+            let mut sweeper = zlock!(self.idle_sweeper);
+            match sweeper.as_ref() {
+                Some(min_timeout) => {
+                    min_timeout.send_if_modified(|min| {
+                        let shorter = timeout < *min;
+                        if shorter {
+                            *min = timeout;
+                        }
+                        shorter
+                    });
+                }
+                None => {
+                    let (min_timeout, rx) = watch::channel(timeout);
+                    let links = Arc::downgrade(&self.idle_links);
+                    let token = self.listeners.token.child_token();
+                    zenoh_runtime::ZRuntime::Acceptor.spawn(idle_sweep_task(links, rx, token));
+                    *sweeper = Some(min_timeout);
+                }
+            }
+            (self.idle_links.clone(), timeout)
+        });
 
-        // Update the endpoint locator address
-        let locator = Locator::new(
-            endpoint.protocol(),
-            format!("{host}:{local_port}"),
-            endpoint.metadata(),
-        )?;
+        // Load the OCSP response periodically to renew the one stapled to the certificate
+        if let Some(period) = ocsp_refresh_interval {
+            let token = self.listeners.token.child_token();
+            zenoh_runtime::ZRuntime::Acceptor.spawn(ocsp_refresh_task(
+                Arc::downgrade(&acceptor),
+                period,
+                token,
+            ));
+        }
+
+        // Run an accept loop per address, all of them feeding the same manager
+        let mut bound = Vec::with_capacity(sockets.len());
+        for (host, socket, local_addr) in sockets {
+            let address = format!("{host}:{}", local_addr.port());
+            let token = self.listeners.token.child_token();
+            let c_token = token.clone();
+            let c_acceptor = acceptor.clone();
+            let c_manager = self.manager.clone();
+            let c_limit = self.limit.clone();
+            let c_accept_config = accept_config.clone();
+
+            let task = async move {
+                accept_task(
+                    socket,
+                    c_acceptor,
+                    c_token,
+                    c_manager,
+                    c_limit,
+                    c_accept_config,
+                )
+                .await
+            };
+
+            let res = async {
+                let listener_endpoint = EndPoint::new(
+                    endpoint.protocol(),
+                    &address,
+                    endpoint.metadata(),
+                    endpoint.config(),
+                )?;
+                self.listeners
+                    .add_listener(listener_endpoint, local_addr, task, token)
+                    .await
+            }
+            .await;
+            if let Err(e) = res {
+                // Tear down the addresses already listening
+                for (addr, _) in bound {
+                    zwrite!(self.listener_states).remove(&addr);
+                    if let Err(e) = self.listeners.del_listener(addr).await {
+                        tracing::warn!("Can not delete the TLS listener on {}: {}", addr, e);
+                    }
+                }
+                return Err(e);
+            }
+            zwrite!(self.listener_states).insert(
+                local_addr,
+                ListenerState {
+                    acceptor: acceptor.clone(),
+                    stats: accept_config.stats.clone(),
+                },
+            );
+            bound.push((local_addr, address));
+        }
 
-        self.listeners
-            .add_listener(endpoint, local_addr, task, token)
-            .await?;
+        // Update the endpoint locator address
+        let mut locators = Vec::with_capacity(bound.len());
+        for (local_addr, address) in bound {
+            let locator = Locator::new(endpoint.protocol(), address, endpoint.metadata())?;
+            locators.push((local_addr, locator));
+        }
+        let (_, locator) = locators
+            .first()
+            .cloned()
+            .ok_or_else(|| zerror!("Can not create a new TLS listener on {epaddr}: no address"))?;
+        zwrite!(self.listener_addrs)
+            .entry(endpoint)
+            .or_default()
+            .extend(locators);
 
         Ok(locator)
     }
 
     async fn del_listener(&self, endpoint: &EndPoint) -> ZResult<()> {
-        let epaddr = endpoint.address();
-        let addr = get_tls_addr(&epaddr).await?;
-        self.listeners.del_listener(addr).await
+        // Tear down the listeners on all the addresses bound along with the given endpoint,
+        // reporting the first error if any
+        let addrs = match self.find_listener(endpoint).await? {
+            Some(key) => zwrite!(self.listener_addrs)
+                .remove(&key)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(addr, _)| addr)
+                .collect(),
+            None => get_tls_listen_addrs(&endpoint.address())
+                .await?
+                .into_iter()
+                .map(|(_, addr)| addr)
+                .collect::<Vec<_>>(),
+        };
+        let mut res = Ok(());
+        for addr in addrs {
+            zwrite!(self.listener_states).remove(&addr);
+            if let Err(e) = self.listeners.del_listener(addr).await {
+                if res.is_ok() {
+                    res = Err(e);
+                }
+            }
+        }
+        res
     }
 
     async fn get_listeners(&self) -> Vec<EndPoint> {
@@ -349,9 +1300,11 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTls {
 
 async fn accept_task(
     socket: TcpListener,
-    acceptor: TlsAcceptor,
+    acceptor: SharedAcceptor,
     token: CancellationToken,
     manager: NewLinkChannelSender,
+    limit: LinkLimit,
+    config: AcceptConfig,
 ) -> ZResult<()> {
     async fn accept(socket: &TcpListener) -> ZResult<(TcpStream, SocketAddr)> {
         let res = socket.accept().await.map_err(|e| zerror!(e))?;
@@ -381,28 +1334,69 @@ async fn accept_task(
                             }
                         };
 
-                        // Accept the TLS connection
-                        let tls_stream = match acceptor.accept(tcp_stream).await {
-                            Ok(stream) => TlsStream::Server(stream),
-                            Err(e) => {
-                                let e = format!("Can not accept TLS connection: {e}");
-                                tracing::warn!("{}", e);
-                                continue;
-                            }
+                        // Close the connection right away once the maximum number of links is reached
+                        let Some(slot) = limit.acquire() else {
+                            tracing::warn!(
+                                "Can not accept TLS connection from {}: maximum number of links reached",
+                                dst_addr
+                            );
+                            continue;
                         };
 
-
-
-                        tracing::debug!("Accepted TLS connection on {:?}: {:?}", src_addr, dst_addr);
-                        // Create the new link object
-                        let link = Arc::new(LinkUnicastTls::new(tls_stream, src_addr, dst_addr));
-
-                        // Communicate the new link to the initial transport manager
-                        if let Err(e) = manager.send_async(LinkUnicast(link)).await {
-                            tracing::error!("{}-{}: {}", file!(), line!(), e)
-                        }
+                        // Perform the TLS handshake in a dedicated task so that a slow or
+                        // misbehaving peer does not stall the accept loop, for a bounded amount
+                        // of time. A panic in the handshake (e.g. in a custom verifier) is
+                        // contained to that task and reported to the error sink, if any.
+                        let handshake = tokio::time::timeout(
+                            Duration::from_millis(*TLS_HANDSHAKE_TIMEOUT),
+                            AssertUnwindSafe(accept_tls(
+                                tcp_stream,
+                                src_addr,
+                                dst_addr,
+                                zread!(acceptor).acceptor.clone(),
+                                manager.clone(),
+                                slot,
+                                config.clone(),
+                            ))
+                            .catch_unwind(),
+                        );
+                        let token = token.clone();
+                        let stats = config.stats.clone();
+                        let error_sink = config.error_sink.clone();
+                        zenoh_runtime::ZRuntime::Acceptor.spawn(async move {
+                            tokio::select! {
+                                _ = token.cancelled() => {}
+                                res = handshake => match res {
+                                    Ok(Ok(())) => {}
+                                    Ok(Err(e)) => {
+                                        stats.handshake_failures.fetch_add(1, Ordering::Relaxed);
+                                        let e = zerror!(
+                                            "TLS handshake with {} panicked: {}",
+                                            dst_addr,
+                                            panic_message(&*e)
+                                        );
+                                        tracing::error!("{}", e);
+                                        if let Some(sink) = error_sink {
+                                            let _ = sink.try_send(ListenerError {
+                                                peer: dst_addr,
+                                                error: e.into(),
+                                            });
+                                        }
+                                    }
+                                    Err(_) => {
+                                        stats.handshake_failures.fetch_add(1, Ordering::Relaxed);
+                                        tracing::warn!(
+                                            "Can not accept TLS connection from {}: handshake timed out after {} ms",
+                                            dst_addr,
+                                            *TLS_HANDSHAKE_TIMEOUT
+                                        );
+                                    }
+                                }
+                            }
+                        });
                     }
                     Err(e) => {
+                        config.stats.accept_errors.fetch_add(1, Ordering::Relaxed);
                         tracing::warn!("{}. Hint: increase the system open file limit.", e);
                         // Throttle the accept loop upon an error
                         // NOTE: This might be due to various factors. However, the most common case is that
@@ -419,3 +1413,169 @@ async fn accept_task(
 
     Ok(())
 }
+
+// Whether a handshake failed because the peers have no ALPN protocol in common, which rustls
+// reports with a cryptic error
+fn is_alpn_mismatch(e: &std::io::Error) -> bool {
+    matches!(
+        e.get_ref().and_then(|e| e.downcast_ref::<rustls::Error>()),
+        Some(
+            rustls::Error::NoApplicationProtocol
+                | rustls::Error::AlertReceived(AlertDescription::NoApplicationProtocol)
+        )
+    )
+}
+
+async fn accept_tls(
+    tcp_stream: TcpStream,
+    src_addr: SocketAddr,
+    dst_addr: SocketAddr,
+    acceptor: TlsAcceptor,
+    manager: NewLinkChannelSender,
+    mut slot: LinkSlot,
+    config: AcceptConfig,
+) {
+    // Accept the TLS connection
+    let tls_stream = match acceptor.accept(tcp_stream).await {
+        Ok(stream) => TlsStream::Server(stream),
+        Err(e) if is_alpn_mismatch(&e) => {
+            config
+                .stats
+                .handshake_failures
+                .fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(
+                "Can not accept TLS connection from {}: no ALPN protocol in common with the client: {}",
+                dst_addr,
+                e
+            );
+            return;
+        }
+        Err(e) => {
+            config
+                .stats
+                .handshake_failures
+                .fetch_add(1, Ordering::Relaxed);
+            tracing::warn!("Can not accept TLS connection from {}: {}", dst_addr, e);
+            return;
+        }
+    };
+
+    tracing::debug!("Accepted TLS connection on {:?}: {:?}", src_addr, dst_addr);
+    // Create the new link object
+    slot.attach(config.stats.clone());
+    let link = Arc::new(LinkUnicastTls::new(
+        tls_stream,
+        src_addr,
+        dst_addr,
+        Some(slot),
+        config.max_in_flight_bytes,
+        config.timeouts,
+    ));
+    if !link.is_peer_allowed(config.fingerprints.as_deref().map(Vec::as_slice)) {
+        tracing::warn!(
+            "Can not accept TLS connection from {}: peer certificate not allowed",
+            dst_addr
+        );
+        config
+            .stats
+            .handshake_failures
+            .fetch_add(1, Ordering::Relaxed);
+        return;
+    }
+    config.stats.accepted.fetch_add(1, Ordering::Relaxed);
+    if let Some((links, timeout)) = config.idle {
+        zlock!(links).push((Arc::downgrade(&link), timeout));
+    }
+
+    // Communicate the new link to the initial transport manager
+    if let Err(e) = manager.send_async(LinkUnicast(link)).await {
+        tracing::error!("{}-{}: {}", file!(), line!(), e)
+    }
+}
+
+// Runs until the listener owning the acceptor is deleted. The response is loaded from the endpoint
+// the current acceptor has been built from, following the reloads of the server config. Upon an
+// error, e.g. if the OCSP response file is being replaced, the previous response is kept until the
+// next refresh.
+async fn ocsp_refresh_task(
+    acceptor: Weak<RwLock<ListenerAcceptor>>,
+    period: Duration,
+    token: CancellationToken,
+) {
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = tokio::time::sleep(period) => {}
+        }
+
+        // The listener has been deleted
+        let Some(acceptor) = acceptor.upgrade() else {
+            break;
+        };
+
+        let (endpoint, cert_resolver) = {
+            let guard = zread!(acceptor);
+            (guard.endpoint.clone(), guard.cert_resolver.clone())
+        };
+        match load_ocsp_response(&endpoint.config()).await {
+            Ok(ocsp) => cert_resolver.set_ocsp_response(ocsp),
+            Err(e) => tracing::warn!(
+                "Cannot refresh the OCSP response of the TLS listener on {}: {}",
+                endpoint,
+                e
+            ),
+        }
+    }
+}
+
+// Minimum period of the idle sweep, bounding its cost with very short idle timeouts
+const IDLE_SWEEP_MIN_PERIOD: Duration = Duration::from_millis(10);
+
+async fn idle_sweep_task(
+    links: Weak<IdleLinkList>,
+    mut min_timeout: watch::Receiver<Duration>,
+    token: CancellationToken,
+) {
+    loop {
+        // Sweep at least twice per idle timeout so that links are not kept much longer than it
+        let period = (*min_timeout.borrow_and_update() / 2).max(IDLE_SWEEP_MIN_PERIOD);
+        tokio::select! {
+            _ = token.cancelled() => break,
+            // A listener with a shorter idle timeout has been added, sweep with a shorter period
+            res = min_timeout.changed() => {
+                if res.is_err() {
+                    break;
+                }
+                continue;
+            }
+            _ = tokio::time::sleep(period) => {}
+        }
+
+        // The manager has been dropped
+        let Some(links) = links.upgrade() else {
+            break;
+        };
+
+        let mut idle = vec![];
+        {
+            let mut guard = zlock!(links);
+            guard.retain(|(link, timeout)| match link.upgrade() {
+                Some(link) if link.idle_time() > *timeout => {
+                    idle.push(link);
+                    false
+                }
+                Some(_) => true,
+                None => false,
+            });
+        }
+
+        for link in idle {
+            tracing::debug!(
+                "Closing TLS link {} idle for more than {:?}",
+                link,
+                link.idle_time()
+            );
+            link.reap();
+        }
+    }
+}
@@ -27,6 +27,8 @@ pub use common::stats;
 
 #[cfg(feature = "shared-memory")]
 mod shm;
+#[cfg(feature = "shared-memory")]
+pub use shm::{ShmFallbackCallback, ShmFallbackReason};
 
 use crate::{multicast::TransportMulticast, unicast::TransportUnicast};
 pub use manager::*;
@@ -110,6 +110,8 @@ pub struct TransportManagerConfig {
 pub struct TransportManagerState {
     pub unicast: TransportManagerStateUnicast,
     pub multicast: TransportManagerStateMulticast,
+    #[cfg(feature = "shared-memory")]
+    pub(crate) shm_fallback: crate::shm::ShmFallback,
 }
 
 pub struct TransportManagerParams {
@@ -133,6 +135,8 @@ pub struct TransportManagerBuilder {
     endpoints: HashMap<String, String>, // (protocol, config)
     tx_threads: usize,
     protocols: Option<Vec<String>>,
+    #[cfg(feature = "shared-memory")]
+    shm_fallback_callback: Option<crate::ShmFallbackCallback>,
 }
 
 impl TransportManagerBuilder {
@@ -206,6 +210,13 @@ impl TransportManagerBuilder {
         self
     }
 
+    /// Sets a callback invoked whenever a message carrying shared memory buffers is sent by copy.
+    #[cfg(feature = "shared-memory")]
+    pub fn shm_fallback_callback(mut self, callback: crate::ShmFallbackCallback) -> Self {
+        self.shm_fallback_callback = Some(callback);
+        self
+    }
+
     pub async fn from_config(mut self, config: &Config) -> ZResult<TransportManagerBuilder> {
         self = self.zid(*config.id());
         if let Some(v) = config.mode() {
@@ -291,6 +302,8 @@ impl TransportManagerBuilder {
         let state = TransportManagerState {
             unicast: unicast.state,
             multicast: multicast.state,
+            #[cfg(feature = "shared-memory")]
+            shm_fallback: crate::shm::ShmFallback::new(self.shm_fallback_callback),
         };
 
         let params = TransportManagerParams { config, state };
@@ -321,6 +334,8 @@ impl Default for TransportManagerBuilder {
             multicast: TransportManagerBuilderMulticast::default(),
             tx_threads: 1,
             protocols: None,
+            #[cfg(feature = "shared-memory")]
+            shm_fallback_callback: None,
         }
     }
 }
@@ -395,6 +410,12 @@ impl TransportManager {
         self.stats.clone()
     }
 
+    /// Returns the number of messages carrying shared memory buffers that have been sent by copy.
+    #[cfg(feature = "shared-memory")]
+    pub fn shm_fallback_count(&self) -> usize {
+        self.state.shm_fallback.count()
+    }
+
     pub async fn close(&self) {
         self.close_unicast().await;
         self.task_controller
@@ -53,12 +53,12 @@ impl TransportMulticastInner {
     pub(super) fn schedule(&self, mut msg: NetworkMessage) -> bool {
         #[cfg(feature = "shared-memory")]
         {
-            let res = if self.manager.config.multicast.is_shm {
-                crate::shm::map_zmsg_to_shminfo(&mut msg)
-            } else {
-                crate::shm::map_zmsg_to_shmbuf(&mut msg, &self.manager.state.multicast.shm.reader)
-            };
-            if let Err(e) = res {
+            if let Err(e) = crate::shm::map_zmsg_to_partner(
+                &mut msg,
+                self.manager.config.multicast.is_shm,
+                &self.manager.state.multicast.shm.reader,
+                &self.manager.state.shm_fallback,
+            ) {
                 tracing::trace!("Failed SHM conversion: {}", e);
                 return false;
             }
@@ -11,6 +11,10 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 use tokio::sync::RwLock;
 use zenoh_buffers::{reader::HasReader, writer::HasWriter, ZBuf, ZSlice, ZSliceKind};
 use zenoh_codec::{RCodec, WCodec, Zenoh080};
@@ -27,6 +31,44 @@ use zenoh_protocol::{
 use zenoh_result::ZResult;
 use zenoh_shm::{SharedMemoryBuf, SharedMemoryBufInfo, SharedMemoryReader};
 
+/// The reason why a message carrying shared memory buffers is sent by copy.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShmFallbackReason {
+    /// Shared memory has not been negotiated with the remote peer of the transport.
+    NotNegotiated,
+}
+
+/// Callback invoked whenever a message carrying shared memory buffers is sent by copy.
+pub type ShmFallbackCallback = Arc<dyn Fn(ShmFallbackReason) + Send + Sync>;
+
+#[derive(Default)]
+pub(crate) struct ShmFallback {
+    count: AtomicUsize,
+    callback: Option<ShmFallbackCallback>,
+}
+
+impl ShmFallback {
+    pub(crate) fn new(callback: Option<ShmFallbackCallback>) -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+            callback,
+        }
+    }
+
+    pub(crate) fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    fn report(&self, reason: ShmFallbackReason) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        tracing::trace!("Shared memory buffers sent by copy: {:?}", reason);
+        if let Some(callback) = self.callback.as_ref() {
+            callback(reason);
+        }
+    }
+}
+
 // Traits
 trait MapShm {
     fn map_to_shminfo(&mut self) -> ZResult<bool>;
@@ -49,7 +91,10 @@ macro_rules! map_to_shmbuf {
             *$ext_shm = None;
             map_zbuf_to_shmbuf($zbuf, $shmr)
         } else {
-            Ok(false)
+            // Local shared memory buffers that have not been mapped to shminfo
+            Ok($zbuf
+                .zslices()
+                .any(|zs| zs.downcast_ref::<SharedMemoryBuf>().is_some()))
         }
     }};
 }
@@ -150,6 +195,22 @@ impl MapShm for Err {
     }
 }
 
+// Maps the shared memory buffers of a message according to whether the transport it is going
+// to be sent on has negotiated shared memory. Otherwise they are sent by copy.
+pub(crate) fn map_zmsg_to_partner(
+    msg: &mut NetworkMessage,
+    is_shm: bool,
+    shmr: &RwLock<SharedMemoryReader>,
+    fallback: &ShmFallback,
+) -> ZResult<()> {
+    if is_shm {
+        map_zmsg_to_shminfo(msg)?;
+    } else if map_zmsg_to_shmbuf(msg, shmr)? {
+        fallback.report(ShmFallbackReason::NotNegotiated);
+    }
+    Ok(())
+}
+
 // ShmBuf -> ShmInfo
 pub fn map_zmsg_to_shminfo(msg: &mut NetworkMessage) -> ZResult<bool> {
     match &mut msg.body {
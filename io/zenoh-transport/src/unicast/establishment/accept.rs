@@ -79,7 +79,7 @@ struct RecvInitSynOut {
     other_zid: ZenohId,
     other_whatami: WhatAmI,
     #[cfg(feature = "shared-memory")]
-    ext_shm: Challenge,
+    ext_shm: Option<Challenge>,
 }
 
 // InitAck
@@ -90,7 +90,7 @@ struct SendInitAckIn {
     other_zid: ZenohId,
     other_whatami: WhatAmI,
     #[cfg(feature = "shared-memory")]
-    ext_shm: Challenge,
+    ext_shm: Option<Challenge>,
 }
 struct SendInitAckOut {
     cookie_nonce: u64,
@@ -706,9 +706,13 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
         #[cfg(feature = "transport_multilink")]
         multilink: state.transport.ext_mlink.multilink(),
         #[cfg(feature = "shared-memory")]
-        is_shm: state.transport.ext_shm.is_shm(),
+        is_shm_tx: state.transport.ext_shm.is_shm_tx(),
+        #[cfg(feature = "shared-memory")]
+        is_shm_rx: state.transport.ext_shm.is_shm_rx(),
         is_lowlatency: state.transport.ext_lowlatency.is_lowlatency(),
     };
+    #[cfg(feature = "shared-memory")]
+    state.transport.ext_shm.log_outcome(&config.zid);
 
     let a_config = TransportLinkUnicastConfig {
         direction: TransportLinkUnicastDirection::Inbound,
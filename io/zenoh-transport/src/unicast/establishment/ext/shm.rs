@@ -16,17 +16,68 @@ use crate::unicast::{
     shared_memory_unicast::{Challenge, SharedMemoryUnicast},
 };
 use async_trait::async_trait;
-use std::convert::TryInto;
+use std::{convert::TryInto, fmt};
 use zenoh_buffers::{
     reader::{DidntRead, HasReader, Reader},
     writer::{DidntWrite, HasWriter, Writer},
 };
 use zenoh_codec::{RCodec, WCodec, Zenoh080};
 use zenoh_core::zasyncwrite;
-use zenoh_protocol::transport::{init, open};
+use zenoh_protocol::{
+    core::ZenohId,
+    transport::{init, open},
+};
 use zenoh_result::{zerror, Error as ZError};
 use zenoh_shm::SharedMemoryBufInfo;
 
+// The version of the shared memory wire format, e.g. of the SharedMemoryBufInfo and of this
+// extension. The versions are:
+// - 1: the format of the peers not advertising any version. The InitAck starts with Alice's
+//      challenge and the OpenAck is 1, shared memory being used in both directions or not at all.
+// - 2: each direction is negotiated separately, see the InitAck flags and the OpenAck bitmask.
+const SHM_VERSION: u8 = 2;
+// The oldest version of the shared memory wire format supported
+const SHM_MIN_VERSION: u8 = 1;
+// The first version of the shared memory wire format negotiating each direction separately
+const SHM_VERSION_PER_DIRECTION: u8 = 2;
+
+// Write the optional trailing fields of InitSyn and InitAck. The token is written empty if none is
+// configured, so that the peers not supporting the version read it as a token and ignore the rest.
+fn write_token_and_version<W>(
+    codec: Zenoh080,
+    writer: &mut W,
+    token: Option<&Vec<u8>>,
+    version: u8,
+) -> Result<(), DidntWrite>
+where
+    W: Writer,
+{
+    let token = token.map_or(&[][..], Vec::as_slice);
+    codec.write(&mut *writer, token)?;
+    codec.write(&mut *writer, version)
+}
+
+// Read the optional trailing fields of InitSyn and InitAck, an empty token meaning no token
+fn read_token_and_version<R>(
+    codec: Zenoh080,
+    reader: &mut R,
+) -> Result<(Option<Vec<u8>>, u8), DidntRead>
+where
+    R: Reader,
+{
+    let token: Option<Vec<u8>> = if reader.can_read() {
+        Some(codec.read(&mut *reader)?).filter(|t: &Vec<u8>| !t.is_empty())
+    } else {
+        None
+    };
+    let version: u8 = if reader.can_read() {
+        codec.read(&mut *reader)?
+    } else {
+        1
+    };
+    Ok((token, version))
+}
+
 /*************************************/
 /*             InitSyn               */
 /*************************************/
@@ -34,8 +85,17 @@ use zenoh_shm::SharedMemoryBufInfo;
 /// +-+-+-+-+-+-+-+-+
 /// ~ ShmMemBufInfo ~
 /// +---------------+
+/// ~  auth token   ~ empty if no token is configured
+/// +---------------+
+/// |    version    |
+/// +---------------+
+///
+/// The token and the version are appended last so that the peers not supporting them remain
+/// compatible.
 pub(crate) struct InitSyn {
     pub(crate) alice_info: SharedMemoryBufInfo,
+    pub(crate) alice_token: Option<Vec<u8>>,
+    pub(crate) alice_version: u8,
 }
 
 // Codec
@@ -47,7 +107,7 @@ where
 
     fn write(self, writer: &mut W, x: &InitSyn) -> Self::Output {
         self.write(&mut *writer, &x.alice_info)?;
-        Ok(())
+        write_token_and_version(self, &mut *writer, x.alice_token.as_ref(), x.alice_version)
     }
 }
 
@@ -59,7 +119,12 @@ where
 
     fn read(self, reader: &mut R) -> Result<InitSyn, Self::Error> {
         let alice_info: SharedMemoryBufInfo = self.read(&mut *reader)?;
-        Ok(InitSyn { alice_info })
+        let (alice_token, alice_version) = read_token_and_version(self, &mut *reader)?;
+        Ok(InitSyn {
+            alice_info,
+            alice_token,
+            alice_version,
+        })
     }
 }
 
@@ -68,13 +133,26 @@ where
 /*************************************/
 ///  7 6 5 4 3 2 1 0
 /// +-+-+-+-+-+-+-+-+
-/// ~   challenge   ~
+/// ~   challenge   ~ 0 if C==0
 /// +---------------+
 /// ~ ShmMemBufInfo ~
 /// +---------------+
+/// ~  auth token   ~ empty if no token is configured
+/// +---------------+
+/// |    version    |
+/// +-+-+-+-+-+-+-+-+
+/// |X X X X X X X C|
+/// +-+-+-+-+-+-+-+-+
+///
+/// - C: Bob has read Alice's challenge, i.e. Alice can send shared memory buffers to Bob
+///
+/// The flags are appended last so that the peers not supporting them remain compatible. Their
+/// challenge is always the one read by Bob, as if C==1.
 struct InitAck {
-    alice_challenge: u64,
+    alice_challenge: Option<Challenge>,
     bob_info: SharedMemoryBufInfo,
+    bob_token: Option<Vec<u8>>,
+    bob_version: u8,
 }
 
 impl<W> WCodec<&InitAck, &mut W> for Zenoh080
@@ -84,9 +162,10 @@ where
     type Output = Result<(), DidntWrite>;
 
     fn write(self, writer: &mut W, x: &InitAck) -> Self::Output {
-        self.write(&mut *writer, x.alice_challenge)?;
+        self.write(&mut *writer, x.alice_challenge.unwrap_or(0))?;
         self.write(&mut *writer, &x.bob_info)?;
-        Ok(())
+        write_token_and_version(self, &mut *writer, x.bob_token.as_ref(), x.bob_version)?;
+        self.write(&mut *writer, u8::from(x.alice_challenge.is_some()))
     }
 }
 
@@ -97,11 +176,19 @@ where
     type Error = DidntRead;
 
     fn read(self, reader: &mut R) -> Result<InitAck, Self::Error> {
-        let alice_challenge: u64 = self.read(&mut *reader)?;
+        let alice_challenge: Challenge = self.read(&mut *reader)?;
         let bob_info: SharedMemoryBufInfo = self.read(&mut *reader)?;
+        let (bob_token, bob_version) = read_token_and_version(self, &mut *reader)?;
+        let flags: u8 = if reader.can_read() {
+            self.read(&mut *reader)?
+        } else {
+            1
+        };
         Ok(InitAck {
-            alice_challenge,
+            alice_challenge: (flags & 1 == 1).then_some(alice_challenge),
             bob_info,
+            bob_token,
+            bob_version,
         })
     }
 }
@@ -113,6 +200,8 @@ where
 /// +-+-+-+-+-+-+-+-+
 /// ~   challenge   ~
 /// +---------------+
+///
+/// Only sent if Alice has read Bob's challenge, i.e. Bob can send shared memory buffers to Alice
 
 /*************************************/
 /*             OpenAck               */
@@ -121,6 +210,99 @@ where
 /// +-+-+-+-+-+-+-+-+
 /// ~      ack      ~
 /// +---------------+
+///
+/// The ack is 1 if shared memory buffers can be sent in both directions. From the version
+/// SHM_VERSION_PER_DIRECTION on, the ack is a bitmask of the directions in which shared memory
+/// buffers can be sent.
+const OPEN_ACK_LEGACY: u64 = 1;
+const OPEN_ACK_ALICE_TO_BOB: u64 = 1;
+const OPEN_ACK_BOB_TO_ALICE: u64 = 1 << 1;
+
+// The first reason why shared memory has been declined in at least one direction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ShmDeclined {
+    Disabled = 1,
+    NotOffered = 2,
+    Decoding = 3,
+    SegmentOpen = 4,
+    RemoteSegmentOpen = 5,
+    ChallengeMismatch = 6,
+    RemoteDeclined = 7,
+    AuthTokenMismatch = 8,
+    VersionMismatch = 9,
+}
+
+impl ShmDeclined {
+    fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            1 => Some(Self::Disabled),
+            2 => Some(Self::NotOffered),
+            3 => Some(Self::Decoding),
+            4 => Some(Self::SegmentOpen),
+            5 => Some(Self::RemoteSegmentOpen),
+            6 => Some(Self::ChallengeMismatch),
+            7 => Some(Self::RemoteDeclined),
+            8 => Some(Self::AuthTokenMismatch),
+            9 => Some(Self::VersionMismatch),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ShmDeclined {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Disabled => "disabled locally",
+            Self::NotOffered => "not offered by the remote",
+            Self::Decoding => "invalid extension received from the remote",
+            Self::SegmentOpen => "unable to open the remote shared memory segment",
+            Self::RemoteSegmentOpen => {
+                "the remote is unable to open the local shared memory segment"
+            }
+            Self::ChallengeMismatch => "challenge mismatch",
+            Self::RemoteDeclined => "declined by the remote",
+            Self::AuthTokenMismatch => "auth token mismatch",
+            Self::VersionMismatch => "unsupported shared memory version",
+        };
+        f.write_str(s)
+    }
+}
+
+// The version of the shared memory wire format to use with a remote advertising `version`
+fn negotiate_version(version: u8) -> Option<u8> {
+    Some(version.min(SHM_VERSION)).filter(|v| *v >= SHM_MIN_VERSION)
+}
+
+// Log a single line summing up the outcome of the negotiation once the establishment completes
+fn log_outcome(
+    zid: &ZenohId,
+    is_shm_tx: bool,
+    is_shm_rx: bool,
+    version: u8,
+    reason: Option<ShmDeclined>,
+) {
+    match reason {
+        None => tracing::info!(
+            "Shm negotiated with {}: tx: true, rx: true, version: {}",
+            zid,
+            version
+        ),
+        Some(ShmDeclined::Disabled) => {
+            tracing::debug!("Shm not negotiated with {}: disabled locally", zid)
+        }
+        Some(reason) if !is_shm_tx && !is_shm_rx => {
+            tracing::info!("Shm not negotiated with {}: {}", zid, reason)
+        }
+        Some(reason) => tracing::info!(
+            "Shm negotiated with {}: tx: {}, rx: {}, version: {}, reason: {}",
+            zid,
+            is_shm_tx,
+            is_shm_rx,
+            version,
+            reason
+        ),
+    }
+}
 
 // Extension Fsm
 pub(crate) struct ShmFsm<'a> {
@@ -131,6 +313,44 @@ impl<'a> ShmFsm<'a> {
     pub(crate) const fn new(inner: &'a SharedMemoryUnicast) -> Self {
         Self { inner }
     }
+
+    // The challenge of the local shared memory segment, as seen locally
+    fn challenge(&self) -> Result<Challenge, ZError> {
+        let bytes: [u8; std::mem::size_of::<Challenge>()] = self
+            .inner
+            .challenge
+            .as_slice()
+            .try_into()
+            .map_err(|e| zerror!("{}", e))?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    // Whether the token presented by the remote is the one configured locally, if any
+    fn is_token_valid(&self, token: Option<&[u8]>) -> bool {
+        match self.inner.auth_token.as_deref() {
+            Some(expected) => token == Some(expected),
+            None => true,
+        }
+    }
+
+    // The challenge of a remote shared memory segment, if it can be read locally
+    async fn read_challenge(&self, info: &SharedMemoryBufInfo, s: &str) -> Option<Challenge> {
+        let shm_buff = match zasyncwrite!(self.inner.reader).read_shmbuf(info) {
+            Ok(buff) => buff,
+            Err(e) => {
+                tracing::trace!("{} {}", s, e);
+                return None;
+            }
+        };
+
+        let Ok(bytes): Result<[u8; std::mem::size_of::<Challenge>()], _> =
+            shm_buff.as_slice().try_into()
+        else {
+            tracing::trace!("{} Failed to read remote Shm.", s);
+            return None;
+        };
+        Some(u64::from_le_bytes(bytes))
+    }
 }
 
 /*************************************/
@@ -138,16 +358,71 @@ impl<'a> ShmFsm<'a> {
 /*************************************/
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) struct StateOpen {
-    is_shm: bool,
+    // Alice can send shared memory buffers to Bob
+    is_shm_tx: bool,
+    // Alice can receive shared memory buffers from Bob
+    is_shm_rx: bool,
+    // The version of the shared memory wire format in use
+    version: u8,
+    reason: Option<ShmDeclined>,
 }
 
 impl StateOpen {
     pub(crate) const fn new(is_shm: bool) -> Self {
-        Self { is_shm }
+        Self {
+            is_shm_tx: is_shm,
+            is_shm_rx: is_shm,
+            version: SHM_VERSION,
+            reason: if is_shm {
+                None
+            } else {
+                Some(ShmDeclined::Disabled)
+            },
+        }
     }
 
     pub(crate) const fn is_shm(&self) -> bool {
-        self.is_shm
+        self.is_shm_tx || self.is_shm_rx
+    }
+
+    pub(crate) const fn is_shm_tx(&self) -> bool {
+        self.is_shm_tx
+    }
+
+    pub(crate) const fn is_shm_rx(&self) -> bool {
+        self.is_shm_rx
+    }
+
+    pub(crate) fn log_outcome(&self, zid: &ZenohId) {
+        log_outcome(
+            zid,
+            self.is_shm_tx,
+            self.is_shm_rx,
+            self.version,
+            self.reason,
+        );
+    }
+
+    fn decline(&mut self, reason: ShmDeclined) {
+        self.decline_tx(reason);
+        self.decline_rx(reason);
+    }
+
+    // Declining one direction declines both with the versions not negotiating them separately
+    fn decline_tx(&mut self, reason: ShmDeclined) {
+        self.is_shm_tx = false;
+        if self.version < SHM_VERSION_PER_DIRECTION {
+            self.is_shm_rx = false;
+        }
+        self.reason.get_or_insert(reason);
+    }
+
+    fn decline_rx(&mut self, reason: ShmDeclined) {
+        self.is_shm_rx = false;
+        if self.version < SHM_VERSION_PER_DIRECTION {
+            self.is_shm_tx = false;
+        }
+        self.reason.get_or_insert(reason);
     }
 }
 
@@ -169,6 +444,8 @@ impl<'a> OpenFsm for &'a ShmFsm<'a> {
 
         let init_syn = InitSyn {
             alice_info: self.inner.challenge.info.clone(),
+            alice_token: self.inner.auth_token.clone(),
+            alice_version: SHM_VERSION,
         };
 
         let codec = Zenoh080::new();
@@ -182,7 +459,7 @@ impl<'a> OpenFsm for &'a ShmFsm<'a> {
     }
 
     type RecvInitAckIn = (&'a mut StateOpen, Option<init::ext::Shm>);
-    type RecvInitAckOut = Challenge;
+    type RecvInitAckOut = Option<Challenge>;
     async fn recv_init_ack(
         self,
         input: Self::RecvInitAckIn,
@@ -191,12 +468,12 @@ impl<'a> OpenFsm for &'a ShmFsm<'a> {
 
         let (state, mut ext) = input;
         if !state.is_shm() {
-            return Ok(0);
+            return Ok(None);
         }
 
         let Some(ext) = ext.take() else {
-            state.is_shm = false;
-            return Ok(0);
+            state.decline(ShmDeclined::NotOffered);
+            return Ok(None);
         };
 
         // Decode the extension
@@ -204,51 +481,51 @@ impl<'a> OpenFsm for &'a ShmFsm<'a> {
         let mut reader = ext.value.reader();
         let Ok(init_ack): Result<InitAck, _> = codec.read(&mut reader) else {
             tracing::trace!("{} Decoding error.", S);
-            state.is_shm = false;
-            return Ok(0);
+            state.decline(ShmDeclined::Decoding);
+            return Ok(None);
+        };
+
+        // Verify that Bob is allowed to use shared memory with Alice
+        if !self.is_token_valid(init_ack.bob_token.as_deref()) {
+            tracing::trace!("{} Auth token mismatch.", S);
+            state.decline(ShmDeclined::AuthTokenMismatch);
+            return Ok(None);
+        }
+
+        // Fall back to no shared memory rather than failing if Bob's version is not supported
+        let Some(version) = negotiate_version(init_ack.bob_version) else {
+            tracing::trace!("{} Unsupported version: {}.", S, init_ack.bob_version);
+            state.decline(ShmDeclined::VersionMismatch);
+            return Ok(None);
         };
+        state.version = version;
 
         // Alice challenge as seen by Alice
-        let bytes: [u8; std::mem::size_of::<Challenge>()] = self
-            .inner
-            .challenge
-            .as_slice()
-            .try_into()
-            .map_err(|e| zerror!("{}", e))?;
-        let challenge = u64::from_le_bytes(bytes);
+        let challenge = self.challenge()?;
 
         // Verify that Bob has correctly read Alice challenge
-        if challenge != init_ack.alice_challenge {
-            tracing::trace!(
-                "{} Challenge mismatch: {} != {}.",
-                S,
-                init_ack.alice_challenge,
-                challenge
-            );
-            state.is_shm = false;
-            return Ok(0);
-        }
-
-        // Read Bob's SharedMemoryBuf
-        let shm_buff = match zasyncwrite!(self.inner.reader).read_shmbuf(&init_ack.bob_info) {
-            Ok(buff) => buff,
-            Err(e) => {
-                tracing::trace!("{} {}", S, e);
-                state.is_shm = false;
-                return Ok(0);
+        match init_ack.alice_challenge {
+            Some(alice_challenge) if alice_challenge == challenge => {}
+            Some(alice_challenge) => {
+                tracing::trace!(
+                    "{} Challenge mismatch: {} != {}.",
+                    S,
+                    alice_challenge,
+                    challenge
+                );
+                state.decline_tx(ShmDeclined::ChallengeMismatch);
             }
-        };
+            None => {
+                tracing::trace!("{} Remote could not read local Shm.", S);
+                state.decline_tx(ShmDeclined::RemoteSegmentOpen);
+            }
+        }
 
         // Bob challenge as seen by Alice
-        let bytes: [u8; std::mem::size_of::<Challenge>()] = match shm_buff.as_slice().try_into() {
-            Ok(bytes) => bytes,
-            Err(_) => {
-                tracing::trace!("{} Failed to read remote Shm.", S);
-                state.is_shm = false;
-                return Ok(0);
-            }
-        };
-        let bob_challenge = u64::from_le_bytes(bytes);
+        let bob_challenge = self.read_challenge(&init_ack.bob_info, S).await;
+        if bob_challenge.is_none() {
+            state.decline_rx(ShmDeclined::SegmentOpen);
+        }
 
         Ok(bob_challenge)
     }
@@ -262,11 +539,11 @@ impl<'a> OpenFsm for &'a ShmFsm<'a> {
         // const S: &str = "Shm extension - Send OpenSyn.";
 
         let (state, bob_challenge) = input;
-        if !state.is_shm() {
+        if !state.is_shm_rx() {
             return Ok(None);
         }
 
-        Ok(Some(open::ext::Shm::new(bob_challenge)))
+        Ok(bob_challenge.map(open::ext::Shm::new))
     }
 
     type RecvOpenAckIn = (&'a mut StateOpen, Option<open::ext::Shm>);
@@ -283,17 +560,31 @@ impl<'a> OpenFsm for &'a ShmFsm<'a> {
         }
 
         let Some(ext) = ext.take() else {
-            state.is_shm = false;
+            state.decline(ShmDeclined::RemoteDeclined);
             return Ok(());
         };
 
-        if ext.value != 1 {
+        if state.version < SHM_VERSION_PER_DIRECTION {
+            if ext.value != OPEN_ACK_LEGACY {
+                tracing::trace!("{} Invalid value.", S);
+                state.decline(ShmDeclined::Decoding);
+            }
+            return Ok(());
+        }
+
+        if ext.value & !(OPEN_ACK_ALICE_TO_BOB | OPEN_ACK_BOB_TO_ALICE) != 0 {
             tracing::trace!("{} Invalid value.", S);
-            state.is_shm = false;
+            state.decline(ShmDeclined::Decoding);
             return Ok(());
         }
 
-        state.is_shm = true;
+        // Bob has the final word on the directions in use
+        if state.is_shm_tx() && ext.value & OPEN_ACK_ALICE_TO_BOB == 0 {
+            state.decline_tx(ShmDeclined::RemoteDeclined);
+        }
+        if state.is_shm_rx() && ext.value & OPEN_ACK_BOB_TO_ALICE == 0 {
+            state.decline_rx(ShmDeclined::RemoteDeclined);
+        }
         Ok(())
     }
 }
@@ -304,23 +595,83 @@ impl<'a> OpenFsm for &'a ShmFsm<'a> {
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) struct StateAccept {
-    is_shm: bool,
+    // Bob can send shared memory buffers to Alice
+    is_shm_tx: bool,
+    // Bob can receive shared memory buffers from Alice
+    is_shm_rx: bool,
+    // The version of the shared memory wire format in use
+    version: u8,
+    reason: Option<ShmDeclined>,
 }
 
 impl StateAccept {
     pub(crate) const fn new(is_shm: bool) -> Self {
-        Self { is_shm }
+        Self {
+            is_shm_tx: is_shm,
+            is_shm_rx: is_shm,
+            version: SHM_VERSION,
+            reason: if is_shm {
+                None
+            } else {
+                Some(ShmDeclined::Disabled)
+            },
+        }
     }
 
     pub(crate) const fn is_shm(&self) -> bool {
-        self.is_shm
+        self.is_shm_tx || self.is_shm_rx
+    }
+
+    pub(crate) const fn is_shm_tx(&self) -> bool {
+        self.is_shm_tx
+    }
+
+    pub(crate) const fn is_shm_rx(&self) -> bool {
+        self.is_shm_rx
+    }
+
+    pub(crate) fn log_outcome(&self, zid: &ZenohId) {
+        log_outcome(
+            zid,
+            self.is_shm_tx,
+            self.is_shm_rx,
+            self.version,
+            self.reason,
+        );
+    }
+
+    fn decline(&mut self, reason: ShmDeclined) {
+        self.decline_tx(reason);
+        self.decline_rx(reason);
+    }
+
+    // Declining one direction declines both with the versions not negotiating them separately
+    fn decline_tx(&mut self, reason: ShmDeclined) {
+        self.is_shm_tx = false;
+        if self.version < SHM_VERSION_PER_DIRECTION {
+            self.is_shm_rx = false;
+        }
+        self.reason.get_or_insert(reason);
+    }
+
+    fn decline_rx(&mut self, reason: ShmDeclined) {
+        self.is_shm_rx = false;
+        if self.version < SHM_VERSION_PER_DIRECTION {
+            self.is_shm_tx = false;
+        }
+        self.reason.get_or_insert(reason);
     }
 
     #[cfg(test)]
     pub(crate) fn rand() -> Self {
         use rand::Rng;
         let mut rng = rand::thread_rng();
-        Self::new(rng.gen_bool(0.5))
+        Self {
+            is_shm_tx: rng.gen_bool(0.5),
+            is_shm_rx: rng.gen_bool(0.5),
+            version: rng.gen(),
+            reason: ShmDeclined::from_u8(rng.gen_range(0..=9)),
+        }
     }
 }
 
@@ -332,8 +683,13 @@ where
     type Output = Result<(), DidntWrite>;
 
     fn write(self, writer: &mut W, x: &StateAccept) -> Self::Output {
-        let is_shm = u8::from(x.is_shm);
-        self.write(&mut *writer, is_shm)?;
+        let is_shm_tx = u8::from(x.is_shm_tx);
+        self.write(&mut *writer, is_shm_tx)?;
+        let is_shm_rx = u8::from(x.is_shm_rx);
+        self.write(&mut *writer, is_shm_rx)?;
+        self.write(&mut *writer, x.version)?;
+        let reason = x.reason.map_or(0, |r| r as u8);
+        self.write(&mut *writer, reason)?;
         Ok(())
     }
 }
@@ -345,9 +701,16 @@ where
     type Error = DidntRead;
 
     fn read(self, reader: &mut R) -> Result<StateAccept, Self::Error> {
-        let is_shm: u8 = self.read(&mut *reader)?;
-        let is_shm = is_shm == 1;
-        Ok(StateAccept { is_shm })
+        let is_shm_tx: u8 = self.read(&mut *reader)?;
+        let is_shm_rx: u8 = self.read(&mut *reader)?;
+        let version: u8 = self.read(&mut *reader)?;
+        let reason: u8 = self.read(&mut *reader)?;
+        Ok(StateAccept {
+            is_shm_tx: is_shm_tx == 1,
+            is_shm_rx: is_shm_rx == 1,
+            version,
+            reason: ShmDeclined::from_u8(reason),
+        })
     }
 }
 
@@ -356,7 +719,7 @@ impl<'a> AcceptFsm for &'a ShmFsm<'a> {
     type Error = ZError;
 
     type RecvInitSynIn = (&'a mut StateAccept, Option<init::ext::Shm>);
-    type RecvInitSynOut = Challenge;
+    type RecvInitSynOut = Option<Challenge>;
     async fn recv_init_syn(
         self,
         input: Self::RecvInitSynIn,
@@ -365,12 +728,12 @@ impl<'a> AcceptFsm for &'a ShmFsm<'a> {
 
         let (state, mut ext) = input;
         if !state.is_shm() {
-            return Ok(0);
+            return Ok(None);
         }
 
         let Some(ext) = ext.take() else {
-            state.is_shm = false;
-            return Ok(0);
+            state.decline(ShmDeclined::NotOffered);
+            return Ok(None);
         };
 
         // Decode the extension
@@ -378,30 +741,30 @@ impl<'a> AcceptFsm for &'a ShmFsm<'a> {
         let mut reader = ext.value.reader();
         let Ok(init_syn): Result<InitSyn, _> = codec.read(&mut reader) else {
             tracing::trace!("{} Decoding error.", S);
-            state.is_shm = false;
-            return Ok(0);
+            state.decline(ShmDeclined::Decoding);
+            return Ok(None);
         };
 
-        // Read Alice's SharedMemoryBuf
-        let shm_buff = match zasyncwrite!(self.inner.reader).read_shmbuf(&init_syn.alice_info) {
-            Ok(buff) => buff,
-            Err(e) => {
-                tracing::trace!("{} {}", S, e);
-                state.is_shm = false;
-                return Ok(0);
-            }
+        // Verify that Alice is allowed to use shared memory with Bob
+        if !self.is_token_valid(init_syn.alice_token.as_deref()) {
+            tracing::trace!("{} Auth token mismatch.", S);
+            state.decline(ShmDeclined::AuthTokenMismatch);
+            return Ok(None);
+        }
+
+        // Fall back to no shared memory rather than failing if Alice's version is not supported
+        let Some(version) = negotiate_version(init_syn.alice_version) else {
+            tracing::trace!("{} Unsupported version: {}.", S, init_syn.alice_version);
+            state.decline(ShmDeclined::VersionMismatch);
+            return Ok(None);
         };
+        state.version = version;
 
         // Alice challenge as seen by Bob
-        let bytes: [u8; std::mem::size_of::<Challenge>()] = match shm_buff.as_slice().try_into() {
-            Ok(bytes) => bytes,
-            Err(_) => {
-                tracing::trace!("{} Failed to read remote Shm.", S);
-                state.is_shm = false;
-                return Ok(0);
-            }
-        };
-        let alice_challenge = u64::from_le_bytes(bytes);
+        let alice_challenge = self.read_challenge(&init_syn.alice_info, S).await;
+        if alice_challenge.is_none() {
+            state.decline_rx(ShmDeclined::SegmentOpen);
+        }
 
         Ok(alice_challenge)
     }
@@ -419,16 +782,18 @@ impl<'a> AcceptFsm for &'a ShmFsm<'a> {
             return Ok(None);
         }
 
-        let init_syn = InitAck {
-            alice_challenge,
+        let init_ack = InitAck {
+            alice_challenge: alice_challenge.filter(|_| state.is_shm_rx()),
             bob_info: self.inner.challenge.info.clone(),
+            bob_token: self.inner.auth_token.clone(),
+            bob_version: SHM_VERSION,
         };
 
         let codec = Zenoh080::new();
         let mut buff = vec![];
         let mut writer = buff.writer();
         codec
-            .write(&mut writer, &init_syn)
+            .write(&mut writer, &init_ack)
             .map_err(|_| zerror!("{} Encoding error", S))?;
 
         Ok(Some(init::ext::Shm::new(buff.into())))
@@ -443,23 +808,18 @@ impl<'a> AcceptFsm for &'a ShmFsm<'a> {
         const S: &str = "Shm extension - Recv OpenSyn.";
 
         let (state, mut ext) = input;
-        if !state.is_shm() {
+        if !state.is_shm_tx() {
             return Ok(());
         }
 
+        // Alice could not read Bob challenge
         let Some(ext) = ext.take() else {
-            state.is_shm = false;
+            state.decline_tx(ShmDeclined::RemoteSegmentOpen);
             return Ok(());
         };
 
         // Bob challenge as seen by Bob
-        let bytes: [u8; std::mem::size_of::<Challenge>()] = self
-            .inner
-            .challenge
-            .as_slice()
-            .try_into()
-            .map_err(|e| zerror!("{}", e))?;
-        let challenge = u64::from_le_bytes(bytes);
+        let challenge = self.challenge()?;
 
         // Verify that Alice has correctly read Bob challenge
         let bob_challnge = ext.value;
@@ -470,8 +830,7 @@ impl<'a> AcceptFsm for &'a ShmFsm<'a> {
                 bob_challnge,
                 challenge
             );
-            state.is_shm = false;
-            return Ok(());
+            state.decline_tx(ShmDeclined::ChallengeMismatch);
         }
 
         Ok(())
@@ -489,7 +848,374 @@ impl<'a> AcceptFsm for &'a ShmFsm<'a> {
             return Ok(None);
         }
 
-        state.is_shm = true;
-        Ok(Some(open::ext::Shm::new(1)))
+        // Both directions are in use with the versions not negotiating them separately
+        if state.version < SHM_VERSION_PER_DIRECTION {
+            return Ok(Some(open::ext::Shm::new(OPEN_ACK_LEGACY)));
+        }
+
+        let mut ack = 0;
+        if state.is_shm_rx() {
+            ack |= OPEN_ACK_ALICE_TO_BOB;
+        }
+        if state.is_shm_tx() {
+            ack |= OPEN_ACK_BOB_TO_ALICE;
+        }
+        Ok(Some(open::ext::Shm::new(ack)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    #[derive(Clone, Default)]
+    struct Logs(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Logs {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // Run the extension handshake between Alice and Bob. If the segment advertised by Alice is
+    // unreachable, Bob is not able to read Alice's challenge.
+    async fn negotiate(
+        alice_state: &mut StateOpen,
+        bob_state: &mut StateAccept,
+        unreachable: bool,
+    ) {
+        negotiate_with_tokens(alice_state, bob_state, unreachable, None, None).await;
+    }
+
+    // Run the extension handshake between Alice and Bob configured with the given auth tokens
+    async fn negotiate_with_tokens(
+        alice_state: &mut StateOpen,
+        bob_state: &mut StateAccept,
+        unreachable: bool,
+        alice_token: Option<&str>,
+        bob_token: Option<&str>,
+    ) {
+        let alice = SharedMemoryUnicast::make(alice_token.map(str::to_owned)).unwrap();
+        let bob = SharedMemoryUnicast::make(bob_token.map(str::to_owned)).unwrap();
+        let alice_fsm = ShmFsm::new(&alice);
+        let bob_fsm = ShmFsm::new(&bob);
+
+        let mut init_syn = (&alice_fsm).send_init_syn(&*alice_state).await.unwrap();
+        if unreachable {
+            let mut alice_info = alice.challenge.info.clone();
+            alice_info.shm_manager = "zshm.unreachable".to_string();
+            let mut buff = vec![];
+            Zenoh080::new()
+                .write(
+                    &mut buff.writer(),
+                    &InitSyn {
+                        alice_info,
+                        alice_token: alice.auth_token.clone(),
+                        alice_version: SHM_VERSION,
+                    },
+                )
+                .unwrap();
+            init_syn = Some(init::ext::Shm::new(buff.into()));
+        }
+
+        let alice_challenge = (&bob_fsm)
+            .recv_init_syn((&mut *bob_state, init_syn))
+            .await
+            .unwrap();
+        let init_ack = (&bob_fsm)
+            .send_init_ack((&*bob_state, alice_challenge))
+            .await
+            .unwrap();
+        let bob_challenge = (&alice_fsm)
+            .recv_init_ack((&mut *alice_state, init_ack))
+            .await
+            .unwrap();
+        let open_syn = (&alice_fsm)
+            .send_open_syn((&*alice_state, bob_challenge))
+            .await
+            .unwrap();
+        (&bob_fsm)
+            .recv_open_syn((&mut *bob_state, open_syn))
+            .await
+            .unwrap();
+        let open_ack = (&bob_fsm).send_open_ack(&mut *bob_state).await.unwrap();
+        (&alice_fsm)
+            .recv_open_ack((&mut *alice_state, open_ack))
+            .await
+            .unwrap();
+    }
+
+    // Return the summary logs of the negotiation on Alice and Bob sides
+    fn outcome(alice_state: &StateOpen, bob_state: &StateAccept) -> (String, String) {
+        let log = |f: &dyn Fn(&ZenohId)| {
+            let logs = Logs::default();
+            let c_logs = logs.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .with_max_level(tracing::Level::INFO)
+                .with_ansi(false)
+                .with_writer(move || c_logs.clone())
+                .finish();
+            tracing::subscriber::with_default(subscriber, || f(&ZenohId::default()));
+            let logs = logs.0.lock().unwrap();
+            String::from_utf8(logs.clone()).unwrap()
+        };
+        (
+            log(&|zid: &ZenohId| alice_state.log_outcome(zid)),
+            log(&|zid: &ZenohId| bob_state.log_outcome(zid)),
+        )
+    }
+
+    #[tokio::test]
+    async fn shm_fsm_both_directions() {
+        let mut alice_state = StateOpen::new(true);
+        let mut bob_state = StateAccept::new(true);
+        negotiate(&mut alice_state, &mut bob_state, false).await;
+
+        assert!(alice_state.is_shm_tx() && alice_state.is_shm_rx());
+        assert!(bob_state.is_shm_tx() && bob_state.is_shm_rx());
+
+        let (alice, bob) = outcome(&alice_state, &bob_state);
+        assert!(alice.contains("INFO"), "{alice}");
+        assert!(alice.contains("Shm negotiated with"), "{alice}");
+        assert!(alice.contains("tx: true, rx: true"), "{alice}");
+        assert!(bob.contains("tx: true, rx: true"), "{bob}");
+    }
+
+    #[tokio::test]
+    async fn shm_fsm_single_direction() {
+        let mut alice_state = StateOpen::new(true);
+        let mut bob_state = StateAccept::new(true);
+        negotiate(&mut alice_state, &mut bob_state, true).await;
+
+        // Only Bob can send shared memory buffers to Alice
+        assert!(!alice_state.is_shm_tx());
+        assert!(alice_state.is_shm_rx());
+        assert!(bob_state.is_shm_tx());
+        assert!(!bob_state.is_shm_rx());
+
+        let (alice, bob) = outcome(&alice_state, &bob_state);
+        assert!(alice.contains("tx: false, rx: true"), "{alice}");
+        assert!(
+            alice.contains("reason: the remote is unable to open the local shared memory segment"),
+            "{alice}"
+        );
+        assert!(bob.contains("tx: true, rx: false"), "{bob}");
+        assert!(
+            bob.contains("reason: unable to open the remote shared memory segment"),
+            "{bob}"
+        );
+    }
+
+    #[tokio::test]
+    async fn shm_fsm_declined() {
+        let mut alice_state = StateOpen::new(true);
+        let mut bob_state = StateAccept::new(false);
+        negotiate(&mut alice_state, &mut bob_state, false).await;
+
+        assert!(!alice_state.is_shm());
+        assert!(!bob_state.is_shm());
+
+        let (alice, bob) = outcome(&alice_state, &bob_state);
+        assert!(alice.contains("INFO"), "{alice}");
+        assert!(
+            alice.contains("Shm not negotiated with")
+                && alice.contains("not offered by the remote"),
+            "{alice}"
+        );
+        // Shm disabled by configuration is only logged at debug level
+        assert!(bob.is_empty(), "{bob}");
+    }
+
+    #[tokio::test]
+    async fn shm_fsm_auth_token() {
+        // Matching tokens enable shared memory
+        let mut alice_state = StateOpen::new(true);
+        let mut bob_state = StateAccept::new(true);
+        negotiate_with_tokens(
+            &mut alice_state,
+            &mut bob_state,
+            false,
+            Some("token"),
+            Some("token"),
+        )
+        .await;
+        assert!(alice_state.is_shm_tx() && alice_state.is_shm_rx());
+        assert!(bob_state.is_shm_tx() && bob_state.is_shm_rx());
+
+        // Mismatching or missing tokens decline shared memory, Alice never sending nor receiving
+        // shared memory buffers
+        for (alice_token, bob_token) in [
+            (Some("token"), Some("other")),
+            (Some("token"), None),
+            (None, Some("token")),
+        ] {
+            let mut alice_state = StateOpen::new(true);
+            let mut bob_state = StateAccept::new(true);
+            negotiate_with_tokens(
+                &mut alice_state,
+                &mut bob_state,
+                false,
+                alice_token,
+                bob_token,
+            )
+            .await;
+            assert!(!alice_state.is_shm());
+            assert!(!bob_state.is_shm_tx());
+        }
+
+        // The token is optional on the wire, as for the peers not supporting it
+        let alice = SharedMemoryUnicast::make(None).unwrap();
+        for alice_token in [None, Some(b"token".to_vec())] {
+            let mut buff = vec![];
+            Zenoh080::new()
+                .write(
+                    &mut buff.writer(),
+                    &InitSyn {
+                        alice_info: alice.challenge.info.clone(),
+                        alice_token: alice_token.clone(),
+                        alice_version: SHM_VERSION,
+                    },
+                )
+                .unwrap();
+            let mut reader = buff.as_slice().reader();
+            let init_syn: InitSyn = Zenoh080::new().read(&mut reader).unwrap();
+            assert_eq!(init_syn.alice_token, alice_token);
+        }
+    }
+    #[tokio::test]
+    async fn shm_fsm_legacy() {
+        let alice = SharedMemoryUnicast::make(None).unwrap();
+        let bob = SharedMemoryUnicast::make(None).unwrap();
+        let alice_fsm = ShmFsm::new(&alice);
+        let bob_fsm = ShmFsm::new(&bob);
+        let codec = Zenoh080::new();
+
+        // Bob not advertising any version replies with the challenge and his segment only
+        let mut alice_state = StateOpen::new(true);
+        let mut buff = vec![];
+        let mut writer = buff.writer();
+        codec
+            .write(&mut writer, alice_fsm.challenge().unwrap())
+            .unwrap();
+        codec.write(&mut writer, &bob.challenge.info).unwrap();
+        let bob_challenge = (&alice_fsm)
+            .recv_init_ack((&mut alice_state, Some(init::ext::Shm::new(buff.into()))))
+            .await
+            .unwrap();
+        assert_eq!(alice_state.version, 1);
+        assert_eq!(bob_challenge, Some(bob_fsm.challenge().unwrap()));
+        (&alice_fsm)
+            .recv_open_ack((&mut alice_state, Some(open::ext::Shm::new(1))))
+            .await
+            .unwrap();
+        assert!(alice_state.is_shm_tx() && alice_state.is_shm_rx());
+
+        // Alice not advertising any version gets the challenge and Bob's segment first, and an
+        // ack of 1 for both directions
+        let mut buff = vec![];
+        codec
+            .write(&mut buff.writer(), &alice.challenge.info)
+            .unwrap();
+        let mut bob_state = StateAccept::new(true);
+        let alice_challenge = (&bob_fsm)
+            .recv_init_syn((&mut bob_state, Some(init::ext::Shm::new(buff.into()))))
+            .await
+            .unwrap();
+        assert_eq!(bob_state.version, 1);
+        let init_ack = (&bob_fsm)
+            .send_init_ack((&bob_state, alice_challenge))
+            .await
+            .unwrap()
+            .unwrap();
+        let mut reader = init_ack.value.reader();
+        let challenge: Challenge = codec.read(&mut reader).unwrap();
+        let bob_info: SharedMemoryBufInfo = codec.read(&mut reader).unwrap();
+        assert_eq!(challenge, alice_fsm.challenge().unwrap());
+        assert_eq!(bob_info, bob.challenge.info);
+        (&bob_fsm)
+            .recv_open_syn((
+                &mut bob_state,
+                Some(open::ext::Shm::new(bob_fsm.challenge().unwrap())),
+            ))
+            .await
+            .unwrap();
+        let open_ack = (&bob_fsm).send_open_ack(&mut bob_state).await.unwrap();
+        assert_eq!(open_ack.map(|ext| ext.value), Some(1));
+
+        // Shared memory is used in both directions or not at all with the version 1
+        let mut alice_info = alice.challenge.info.clone();
+        alice_info.shm_manager = "zshm.unreachable".to_string();
+        let mut buff = vec![];
+        codec.write(&mut buff.writer(), &alice_info).unwrap();
+        let mut bob_state = StateAccept::new(true);
+        let alice_challenge = (&bob_fsm)
+            .recv_init_syn((&mut bob_state, Some(init::ext::Shm::new(buff.into()))))
+            .await
+            .unwrap();
+        assert!(!bob_state.is_shm());
+        let init_ack = (&bob_fsm)
+            .send_init_ack((&bob_state, alice_challenge))
+            .await
+            .unwrap();
+        assert!(init_ack.is_none());
+    }
+
+    #[tokio::test]
+    async fn shm_fsm_version() {
+        let alice = SharedMemoryUnicast::make(None).unwrap();
+        let bob = SharedMemoryUnicast::make(None).unwrap();
+        let bob_fsm = ShmFsm::new(&bob);
+        let init_syn = |alice_version| {
+            let mut buff = vec![];
+            Zenoh080::new()
+                .write(
+                    &mut buff.writer(),
+                    &InitSyn {
+                        alice_info: alice.challenge.info.clone(),
+                        alice_token: None,
+                        alice_version,
+                    },
+                )
+                .unwrap();
+            Some(init::ext::Shm::new(buff.into()))
+        };
+
+        // A newer version falls back to the local one
+        let mut bob_state = StateAccept::new(true);
+        (&bob_fsm)
+            .recv_init_syn((&mut bob_state, init_syn(SHM_VERSION + 1)))
+            .await
+            .unwrap();
+        assert!(bob_state.is_shm_tx() && bob_state.is_shm_rx());
+        assert_eq!(bob_state.version, SHM_VERSION);
+
+        // An unsupported version disables shared memory without failing the establishment
+        let mut bob_state = StateAccept::new(true);
+        let alice_challenge = (&bob_fsm)
+            .recv_init_syn((&mut bob_state, init_syn(SHM_MIN_VERSION - 1)))
+            .await
+            .unwrap();
+        assert!(alice_challenge.is_none());
+        assert!(!bob_state.is_shm());
+        assert_eq!(bob_state.reason, Some(ShmDeclined::VersionMismatch));
+
+        // The peers not advertising any version use the version 1
+        let mut buff = vec![];
+        Zenoh080::new()
+            .write(&mut buff.writer(), &alice.challenge.info)
+            .unwrap();
+        let mut reader = buff.as_slice().reader();
+        let init_syn: InitSyn = Zenoh080::new().read(&mut reader).unwrap();
+        assert_eq!(init_syn.alice_token, None);
+        assert_eq!(init_syn.alice_version, 1);
     }
 }
@@ -81,7 +81,7 @@ struct RecvInitAckOut {
     other_whatami: WhatAmI,
     other_cookie: ZSlice,
     #[cfg(feature = "shared-memory")]
-    ext_shm: Challenge,
+    ext_shm: Option<Challenge>,
 }
 
 // OpenSyn
@@ -91,7 +91,7 @@ struct SendOpenSynIn {
     other_zid: ZenohId,
     other_cookie: ZSlice,
     #[cfg(feature = "shared-memory")]
-    ext_shm: Challenge,
+    ext_shm: Option<Challenge>,
 }
 
 struct SendOpenSynOut {
@@ -619,9 +619,13 @@ pub(crate) async fn open_link(
         #[cfg(feature = "transport_multilink")]
         multilink: state.transport.ext_mlink.multilink(),
         #[cfg(feature = "shared-memory")]
-        is_shm: state.transport.ext_shm.is_shm(),
+        is_shm_tx: state.transport.ext_shm.is_shm_tx(),
+        #[cfg(feature = "shared-memory")]
+        is_shm_rx: state.transport.ext_shm.is_shm_rx(),
         is_lowlatency: state.transport.ext_lowlatency.is_lowlatency(),
     };
+    #[cfg(feature = "shared-memory")]
+    state.transport.ext_shm.log_outcome(&config.zid);
 
     let o_config = TransportLinkUnicastConfig {
         direction: TransportLinkUnicastDirection::Outbound,
@@ -35,7 +35,7 @@ impl TransportUnicastLowlatency {
         if let Some(callback) = callback.as_ref() {
             #[cfg(feature = "shared-memory")]
             {
-                if self.config.is_shm {
+                if self.config.is_shm_rx {
                     crate::shm::map_zmsg_to_shmbuf(&mut msg, &self.manager.shm().reader)?;
                 }
             }
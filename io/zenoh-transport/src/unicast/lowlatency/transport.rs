@@ -193,7 +193,7 @@ impl TransportUnicastTrait for TransportUnicastLowlatency {
 
     #[cfg(feature = "shared-memory")]
     fn is_shm(&self) -> bool {
-        self.config.is_shm
+        self.config.is_shm_tx || self.config.is_shm_rx
     }
 
     fn is_qos(&self) -> bool {
@@ -108,6 +108,8 @@ pub struct TransportManagerBuilderUnicast {
     pub(super) max_links: usize,
     #[cfg(feature = "shared-memory")]
     pub(super) is_shm: bool,
+    #[cfg(feature = "shared-memory")]
+    pub(super) shm_auth_token: Option<String>,
     #[cfg(feature = "transport_auth")]
     pub(super) authenticator: Auth,
     pub(super) is_lowlatency: bool,
@@ -169,6 +171,12 @@ impl TransportManagerBuilderUnicast {
         self
     }
 
+    #[cfg(feature = "shared-memory")]
+    pub fn shm_auth_token(mut self, auth_token: Option<String>) -> Self {
+        self.shm_auth_token = auth_token;
+        self
+    }
+
     #[cfg(feature = "transport_compression")]
     pub fn compression(mut self, is_compression: bool) -> Self {
         self.is_compression = is_compression;
@@ -195,6 +203,7 @@ impl TransportManagerBuilderUnicast {
         #[cfg(feature = "shared-memory")]
         {
             self = self.shm(*config.transport().shared_memory().enabled());
+            self = self.shm_auth_token(config.transport().shared_memory().auth_token().clone());
         }
         #[cfg(feature = "transport_auth")]
         {
@@ -239,7 +248,7 @@ impl TransportManagerBuilderUnicast {
             #[cfg(feature = "transport_multilink")]
             multilink: Arc::new(MultiLink::make(prng)?),
             #[cfg(feature = "shared-memory")]
-            shm: Arc::new(SharedMemoryUnicast::make()?),
+            shm: Arc::new(SharedMemoryUnicast::make(self.shm_auth_token)?),
             #[cfg(feature = "transport_auth")]
             authenticator: Arc::new(self.authenticator),
         };
@@ -271,6 +280,8 @@ impl Default for TransportManagerBuilderUnicast {
             max_links: *transport.max_links(),
             #[cfg(feature = "shared-memory")]
             is_shm: *shm.enabled(),
+            #[cfg(feature = "shared-memory")]
+            shm_auth_token: shm.auth_token().clone(),
             #[cfg(feature = "transport_auth")]
             authenticator: Auth::default(),
             is_lowlatency: *transport.lowlatency(),
@@ -595,14 +606,15 @@ impl TransportManager {
             "shared-memory",
             {
                 tracing::debug!(
-            "New transport opened between {} and {} - whatami: {}, sn resolution: {:?}, initial sn: {:?}, qos: {}, shm: {}, multilink: {}, lowlatency: {}",
+            "New transport opened between {} and {} - whatami: {}, sn resolution: {:?}, initial sn: {:?}, qos: {}, shm tx: {}, shm rx: {}, multilink: {}, lowlatency: {}",
             self.config.zid,
             config.zid,
             config.whatami,
             config.sn_resolution,
             config.tx_initial_sn,
             config.is_qos,
-            config.is_shm,
+            config.is_shm_tx,
+            config.is_shm_rx,
             is_multilink,
             config.is_lowlatency
         );
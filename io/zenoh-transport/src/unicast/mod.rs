@@ -54,7 +54,9 @@ pub(crate) struct TransportConfigUnicast {
     #[cfg(feature = "transport_multilink")]
     pub(crate) multilink: Option<ZPublicKey>,
     #[cfg(feature = "shared-memory")]
-    pub(crate) is_shm: bool,
+    pub(crate) is_shm_tx: bool,
+    #[cfg(feature = "shared-memory")]
+    pub(crate) is_shm_rx: bool,
     pub(crate) is_lowlatency: bool,
 }
 
@@ -30,13 +30,21 @@ pub(crate) struct SharedMemoryUnicast {
     pub(crate) challenge: SharedMemoryBuf,
     pub(crate) _manager: SharedMemoryManager,
     pub(crate) reader: RwLock<SharedMemoryReader>,
+    // The pre-shared token presented to and expected from the remote, if any
+    pub(crate) auth_token: Option<Vec<u8>>,
 }
 
 unsafe impl Sync for SharedMemoryUnicast {}
 
 impl SharedMemoryUnicast {
-    pub fn make() -> ZResult<SharedMemoryUnicast> {
-        // Create a challenge for session establishment
+    pub fn make(auth_token: Option<String>) -> ZResult<SharedMemoryUnicast> {
+        // Create a challenge for session establishment. Reading it back from the shared memory
+        // segment proves that the remote runs on the same host, so it must not be guessable from
+        // the network: it is drawn from a CSPRNG seeded by the OS and spans 64 bits. The challenge
+        // is drawn once per transport manager rather than per establishment, since it lives in
+        // the segment handed over to every remote. A challenge eavesdropped on an unencrypted link
+        // could thus be replayed by an off-host peer, which would then only receive buffer infos
+        // pointing to a segment it can not map, never their content.
         let mut prng = PseudoRng::from_entropy();
         let nonce = prng.gen::<Challenge>();
         let size = std::mem::size_of::<Challenge>();
@@ -51,6 +59,7 @@ impl SharedMemoryUnicast {
             challenge,
             _manager,
             reader: RwLock::new(SharedMemoryReader::new()),
+            auth_token: auth_token.map(String::into_bytes),
         };
         Ok(shmauth)
     }
@@ -42,7 +42,7 @@ impl TransportUnicastUniversal {
     ) -> ZResult<()> {
         #[cfg(feature = "shared-memory")]
         {
-            if self.config.is_shm {
+            if self.config.is_shm_rx {
                 crate::shm::map_zmsg_to_shmbuf(&mut msg, &self.manager.state.unicast.shm.reader)?;
             }
         }
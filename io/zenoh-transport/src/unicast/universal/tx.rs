@@ -61,12 +61,12 @@ impl TransportUnicastUniversal {
     pub(crate) fn internal_schedule(&self, mut msg: NetworkMessage) -> bool {
         #[cfg(feature = "shared-memory")]
         {
-            let res = if self.config.is_shm {
-                crate::shm::map_zmsg_to_shminfo(&mut msg)
-            } else {
-                crate::shm::map_zmsg_to_shmbuf(&mut msg, &self.manager.shm().reader)
-            };
-            if let Err(e) = res {
+            if let Err(e) = crate::shm::map_zmsg_to_partner(
+                &mut msg,
+                self.config.is_shm_tx,
+                &self.manager.shm().reader,
+                &self.manager.state.shm_fallback,
+            ) {
                 tracing::trace!("Failed SHM conversion: {}", e);
                 return false;
             }
@@ -38,8 +38,9 @@ mod tests {
     use zenoh_result::{zerror, ZResult};
     use zenoh_shm::{SharedMemoryBuf, SharedMemoryManager};
     use zenoh_transport::{
-        multicast::TransportMulticast, unicast::TransportUnicast, TransportEventHandler,
-        TransportManager, TransportMulticastEventHandler, TransportPeer, TransportPeerEventHandler,
+        multicast::TransportMulticast, unicast::TransportUnicast, ShmFallbackReason,
+        TransportEventHandler, TransportManager, TransportMulticastEventHandler, TransportPeer,
+        TransportPeerEventHandler,
     };
 
     const TIMEOUT: Duration = Duration::from_secs(60);
@@ -171,9 +172,15 @@ mod tests {
 
         // Create a peer manager with shared-memory authenticator enabled
         let peer_shm01_handler = Arc::new(SHPeer::new(true));
+        let fallbacks = Arc::new(AtomicUsize::new(0));
+        let c_fallbacks = fallbacks.clone();
         let peer_shm01_manager = TransportManager::builder()
             .whatami(WhatAmI::Peer)
             .zid(peer_shm01)
+            .shm_fallback_callback(Arc::new(move |reason| {
+                assert_eq!(reason, ShmFallbackReason::NotNegotiated);
+                c_fallbacks.fetch_add(1, Ordering::SeqCst);
+            }))
             .unicast(
                 TransportManager::config_unicast()
                     .shm(true)
@@ -290,6 +297,8 @@ mod tests {
                 tokio::time::sleep(SLEEP).await;
             }
         });
+        assert_eq!(peer_shm01_manager.shm_fallback_count(), 0);
+        assert_eq!(fallbacks.load(Ordering::SeqCst), 0);
 
         // Send the message
         println!("Transport SHM [4a]");
@@ -338,6 +347,9 @@ mod tests {
                 tokio::time::sleep(SLEEP).await;
             }
         });
+        // The shared memory buffers have been sent by copy to the non-SHM peer
+        assert_eq!(peer_shm01_manager.shm_fallback_count(), MSG_COUNT);
+        assert_eq!(fallbacks.load(Ordering::SeqCst), MSG_COUNT);
 
         // Wait a little bit
         tokio::time::sleep(SLEEP).await;
@@ -58,6 +58,13 @@ pub struct StorageConfig {
     pub volume_id: String,
     pub volume_cfg: Value,
     pub garbage_collection_config: GarbageCollectionConfig,
+    // Note: when set, writes exceeding this rate are queued until the volume can accept them,
+    //       and the values replied to wildcard queries are fetched at this rate
+    pub max_writes_per_sec: Option<u64>,
+    // Note: bound of the queue of rate limited writes, the oldest ones being dropped beyond it
+    pub max_pending_writes: Option<usize>,
+    // Note: name of a sample interceptor registered to the storage manager, applied before storing
+    pub interceptor: Option<String>,
     // Note: ReplicaConfig is optional. Alignment will be performed only if it is a replica
     pub replica_config: Option<ReplicaConfig>,
 }
@@ -447,6 +454,36 @@ impl StorageConfig {
             }
             None => GarbageCollectionConfig::default(),
         };
+        let max_writes_per_sec = match config.get("max_writes_per_sec") {
+            Some(Value::Number(n)) => match n.as_u64() {
+                Some(n) if n > 0 => Some(n),
+                _ => bail!("Invalid value for field `max_writes_per_sec` of storage `{}`. Only strictly positive integer values are accepted.", storage_name),
+            },
+            None => None,
+            _ => bail!(
+                "Invalid type for field `max_writes_per_sec` of storage `{}`. Only integer values are accepted.",
+                storage_name
+            ),
+        };
+        let max_pending_writes = match config.get("max_pending_writes") {
+            Some(Value::Number(n)) => match n.as_u64() {
+                Some(n) if n > 0 => Some(n as usize),
+                _ => bail!("Invalid value for field `max_pending_writes` of storage `{}`. Only strictly positive integer values are accepted.", storage_name),
+            },
+            None => None,
+            _ => bail!(
+                "Invalid type for field `max_pending_writes` of storage `{}`. Only integer values are accepted.",
+                storage_name
+            ),
+        };
+        let interceptor = match config.get("interceptor") {
+            Some(Value::String(s)) => Some(s.clone()),
+            None => None,
+            _ => bail!(
+                "Invalid type for field `interceptor` of storage `{}`. Only strings are accepted.",
+                storage_name
+            ),
+        };
         let replica_config = match config.get("replica_config") {
             Some(s) => {
                 let mut replica_config = ReplicaConfig::default();
@@ -487,6 +524,9 @@ impl StorageConfig {
             volume_id,
             volume_cfg,
             garbage_collection_config,
+            max_writes_per_sec,
+            max_pending_writes,
+            interceptor,
             replica_config,
         })
     }
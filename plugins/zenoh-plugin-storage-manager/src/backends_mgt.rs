@@ -12,6 +12,7 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::storages_mgt::*;
+use crate::interceptor::SampleInterceptor;
 use flume::Sender;
 use std::sync::Arc;
 use zenoh::prelude::r#async::*;
@@ -25,6 +26,7 @@ pub struct StoreIntercept {
     pub capability: Capability,
     pub in_interceptor: Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>>,
     pub out_interceptor: Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>>,
+    pub sample_interceptor: Option<Arc<dyn SampleInterceptor>>,
 }
 
 pub(crate) async fn create_and_start_storage(
@@ -33,6 +35,7 @@ pub(crate) async fn create_and_start_storage(
     backend: &VolumeInstance,
     in_interceptor: Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>>,
     out_interceptor: Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>>,
+    sample_interceptor: Option<Arc<dyn SampleInterceptor>>,
     zenoh: Arc<Session>,
 ) -> ZResult<Sender<StorageMessage>> {
     tracing::trace!("Create storage '{}'", &admin_key);
@@ -43,6 +46,7 @@ pub(crate) async fn create_and_start_storage(
         capability,
         in_interceptor,
         out_interceptor,
+        sample_interceptor,
     };
 
     start_storage(store_intercept, config, admin_key, zenoh).await
@@ -0,0 +1,76 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use zenoh::prelude::r#async::*;
+use zenoh_core::{lazy_static, zlock};
+use zenoh_result::ZResult;
+
+/// A hook called by a storage on each incoming sample before committing it.
+///
+/// A storage uses the interceptor registered under the name given by its `interceptor`
+/// configuration field. For each received sample, the storage applies in order:
+///  1. the incoming data interceptor of its volume (if any),
+///  2. its [`SampleInterceptor`] (if any),
+///  3. the `max_writes_per_sec` rate limiter (if configured),
+///  4. the conflict resolution based on timestamps,
+///  5. the write into the volume.
+///
+/// Rejected samples are thus neither rate-limited nor stored.
+pub trait SampleInterceptor: Send + Sync {
+    /// Returns the sample to be stored, possibly rewritten, or an error to reject it.
+    /// A rejected sample is dropped and the error is logged.
+    /// The timestamp of the returned sample is the one used for conflict resolution,
+    /// so a rewritten sample should keep the timestamp of the original one.
+    fn intercept(&self, sample: Sample) -> ZResult<Sample>;
+}
+
+impl<F> SampleInterceptor for F
+where
+    F: Fn(Sample) -> ZResult<Sample> + Send + Sync,
+{
+    fn intercept(&self, sample: Sample) -> ZResult<Sample> {
+        self(sample)
+    }
+}
+
+// NOTE: unreachable from outside a dynamically loaded plugin, see register_sample_interceptor
+lazy_static! {
+    static ref INTERCEPTORS: Mutex<HashMap<String, Arc<dyn SampleInterceptor>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Registers a [`SampleInterceptor`] under `name`, replacing any interceptor previously
+/// registered under the same name. Storages resolve their interceptor when they are spawned,
+/// so it must be registered before the storage manager plugin is started.
+///
+/// The registry is a static of this crate, so registration only works when the storage
+/// manager is statically linked with the application starting it. A storage manager loaded by
+/// zenohd as a dynamic plugin (the `dynamic_plugin` feature) holds its own copy of the
+/// registry, which this function can't reach.
+pub fn register_sample_interceptor<S: Into<String>>(
+    name: S,
+    interceptor: Arc<dyn SampleInterceptor>,
+) {
+    zlock!(INTERCEPTORS).insert(name.into(), interceptor);
+}
+
+/// Unregisters the [`SampleInterceptor`] registered under `name`, returning it if any.
+pub fn unregister_sample_interceptor(name: &str) -> Option<Arc<dyn SampleInterceptor>> {
+    zlock!(INTERCEPTORS).remove(name)
+}
+
+pub(crate) fn get_sample_interceptor(name: &str) -> Option<Arc<dyn SampleInterceptor>> {
+    zlock!(INTERCEPTORS).get(name).cloned()
+}
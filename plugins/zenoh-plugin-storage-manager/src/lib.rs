@@ -48,8 +48,13 @@ use zenoh_util::LibLoader;
 
 mod backends_mgt;
 use backends_mgt::*;
+mod interceptor;
+pub use interceptor::{
+    register_sample_interceptor, unregister_sample_interceptor, SampleInterceptor,
+};
 mod memory_backend;
 mod replica;
+pub use replica::storage::{ELIDED_VALUE_ENCODING, KEYS_ONLY_SEL_PARAM};
 mod storages_mgt;
 
 #[cfg(feature = "dynamic_plugin")]
@@ -247,12 +252,20 @@ impl StorageRuntimeInner {
         );
         let in_interceptor = backend.instance().incoming_data_interceptor();
         let out_interceptor = backend.instance().outgoing_data_interceptor();
+        let sample_interceptor = match &storage.interceptor {
+            Some(name) => Some(interceptor::get_sample_interceptor(name).ok_or(format!(
+                "Cannot find interceptor '{}' for storage '{}'",
+                name, storage_name
+            ))?),
+            None => None,
+        };
         let stopper = async_std::task::block_on(create_and_start_storage(
             admin_key,
             storage.clone(),
             backend.instance(),
             in_interceptor,
             out_interceptor,
+            sample_interceptor,
             self.session.clone(),
         ))?;
         self.storages
@@ -12,15 +12,18 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use crate::backends_mgt::StoreIntercept;
+use crate::interceptor::SampleInterceptor;
 use crate::storages_mgt::StorageMessage;
 use async_std::sync::Arc;
 use async_std::sync::{Mutex, RwLock};
+use async_std::task;
 use async_trait::async_trait;
-use flume::{Receiver, Sender};
+use flume::{Receiver, Sender, TrySendError};
 use futures::select;
 use std::collections::{HashMap, HashSet};
 use std::str::{self, FromStr};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Weak;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use zenoh::buffers::ZBuf;
 use zenoh::prelude::r#async::*;
 use zenoh::query::ConsolidationMode;
@@ -37,6 +40,13 @@ use zenoh_util::{zenoh_home, Timed, TimedEvent, Timer};
 
 pub const WILDCARD_UPDATES_FILENAME: &str = "wildcard_updates";
 pub const TOMBSTONE_FILENAME: &str = "tombstones";
+/// Selector parameter requesting a storage to reply only with the keys it holds and their timestamps.
+pub const KEYS_ONLY_SEL_PARAM: &str = "_keys_only";
+/// Encoding of the empty values replied to a query with [`KEYS_ONLY_SEL_PARAM`], to distinguish an
+/// elided value from an actually empty one.
+pub const ELIDED_VALUE_ENCODING: &str = "zenoh/elided";
+/// Default bound of the queue of rate limited writes, in seconds worth of writes.
+const DEFAULT_PENDING_WRITES_SECS: usize = 10;
 
 #[derive(Clone)]
 struct Update {
@@ -62,6 +72,9 @@ pub struct StorageService {
     wildcard_updates: Arc<RwLock<KeBoxTree<Update, UnknownWildness, KeyedSetProvider>>>,
     in_interceptor: Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>>,
     out_interceptor: Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>>,
+    sample_interceptor: Option<Arc<dyn SampleInterceptor>>,
+    write_queue: Option<WriteQueue>,
+    reply_limiter: Option<Mutex<WriteLimiter>>,
     replication: Option<ReplicationService>,
 }
 
@@ -75,7 +88,7 @@ impl StorageService {
         replication: Option<ReplicationService>,
     ) {
         // @TODO: optimization: if read_cost is high for the storage, initialize a cache for the latest value
-        let mut storage_service = StorageService {
+        let storage_service = StorageService {
             session,
             key_expr: config.key_expr,
             complete: config.complete,
@@ -87,6 +100,16 @@ impl StorageService {
             wildcard_updates: Arc::new(RwLock::new(KeBoxTree::default())),
             in_interceptor: store_intercept.in_interceptor,
             out_interceptor: store_intercept.out_interceptor,
+            sample_interceptor: store_intercept.sample_interceptor,
+            write_queue: config.max_writes_per_sec.map(|r| {
+                let capacity = config
+                    .max_pending_writes
+                    .unwrap_or((r as usize).saturating_mul(DEFAULT_PENDING_WRITES_SECS));
+                WriteQueue::new(r, capacity)
+            }),
+            reply_limiter: config
+                .max_writes_per_sec
+                .map(|r| Mutex::new(WriteLimiter::new(r))),
             replication,
         };
         if storage_service
@@ -116,16 +139,27 @@ impl StorageService {
                 }
             }
         }
-        storage_service
+        Arc::new(storage_service)
             .start_storage_queryable_subscriber(rx, config.garbage_collection_config)
             .await
     }
 
     async fn start_storage_queryable_subscriber(
-        &mut self,
+        self: Arc<Self>,
         rx: Receiver<StorageMessage>,
         gc_config: GarbageCollectionConfig,
     ) {
+        // commit the rate limited writes (if any) in a dedicated task, so that the queries and
+        // the replication go on while the writes are throttled
+        if let Some(ref queue) = self.write_queue {
+            let limiter = WriteLimiter::new(queue.max_writes_per_sec);
+            task::spawn(Self::run_writer(
+                Arc::downgrade(&self),
+                queue.rx.clone(),
+                limiter,
+            ));
+        }
+
         self.initialize_if_empty().await;
 
         // start periodic GC event
@@ -269,7 +303,45 @@ impl StorageService {
         } else {
             sample
         };
+        // Call the storage's sample interceptor (if any), which may reject the sample
+        let sample = match self.sample_interceptor {
+            Some(ref interceptor) => match interceptor.intercept(sample) {
+                Ok(sample) => sample,
+                Err(e) => {
+                    tracing::warn!("Storage '{}' rejected a sample: {}", self.name, e);
+                    return;
+                }
+            },
+            None => sample,
+        };
+
+        match self.write_queue {
+            Some(ref queue) => {
+                if let Some(dropped) = queue.push(sample) {
+                    tracing::warn!(
+                        "Storage '{}' dropped the pending write on {}: too many pending writes",
+                        self.name,
+                        dropped.key_expr
+                    );
+                }
+            }
+            None => self.store_sample(sample, None).await,
+        }
+    }
+
+    // Commit the pending writes in their arrival order, until the storage is dropped
+    async fn run_writer(this: Weak<Self>, rx: Receiver<Sample>, mut limiter: WriteLimiter) {
+        while let Ok(sample) = rx.recv_async().await {
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            this.store_sample(sample, Some(&mut limiter)).await;
+        }
+    }
 
+    // Resolve the conflicts of the sample and write it into the storage, waiting for the rate
+    // limiter (if any) before each key it matches
+    async fn store_sample(&self, sample: Sample, mut limiter: Option<&mut WriteLimiter>) {
         // if wildcard, update wildcard_updates
         if sample.key_expr.is_wild() {
             self.register_wildcard_update(sample.clone()).await;
@@ -287,6 +359,11 @@ impl StorageService {
         );
 
         for k in matching_keys {
+            // wait for the rate limiter (if any) before resolving conflicts, so that an update
+            // which became outdated while queued is still discarded based on its timestamp
+            if let Some(limiter) = limiter.as_deref_mut() {
+                limiter.acquire().await;
+            }
             if !self
                 .is_deleted(&k.clone(), sample.get_timestamp().unwrap())
                 .await
@@ -499,11 +576,27 @@ impl StorageService {
             }
         };
         tracing::trace!("[STORAGE] Processing query on key_expr: {}", q.key_expr());
+        match q.parameters().get_bools([KEYS_ONLY_SEL_PARAM]) {
+            Ok([true]) => return self.reply_keys_only(&q).await,
+            Ok([false]) => (),
+            Err(e) => {
+                tracing::warn!("Storage '{}' received an invalid query: {}", self.name, e);
+                return;
+            }
+        }
         if q.key_expr().is_wild() {
-            // resolve key expr into individual keys
+            // resolve key expr into individual keys, then fetch and reply the values one at a
+            // time. Only the matching keys are all held in memory at once (as returned by
+            // get_all_entries), not their values. The storage is locked for each key only,
+            // letting the writes go on while replying: a key removed in the meantime is not
+            // replied, and a value updated in the meantime is replied in its latest version.
+            // The values are fetched at the max_writes_per_sec rate (if configured), with a
+            // budget of their own so that the queries do not starve the writes.
             let matching_keys = self.get_matching_keys(q.key_expr()).await;
-            let mut storage = self.storage.lock().await;
             for key in matching_keys {
+                if let Some(ref limiter) = self.reply_limiter {
+                    limiter.lock().await.acquire().await;
+                }
                 let stripped_key = match self.strip_prefix(&key.clone().into()) {
                     Ok(k) => k,
                     Err(e) => {
@@ -512,7 +605,13 @@ impl StorageService {
                         return;
                     }
                 };
-                match storage.get(stripped_key, q.parameters()).await {
+                let stored_data = self
+                    .storage
+                    .lock()
+                    .await
+                    .get(stripped_key, q.parameters())
+                    .await;
+                match stored_data {
                     Ok(stored_data) => {
                         for entry in stored_data {
                             let sample = Sample::new(key.clone(), entry.value)
@@ -537,7 +636,6 @@ impl StorageService {
                     }
                 };
             }
-            drop(storage);
         } else {
             let stripped_key = match self.strip_prefix(q.key_expr()) {
                 Ok(k) => k,
@@ -575,6 +673,42 @@ impl StorageService {
         }
     }
 
+    // Replies with the matching keys and their timestamps, without fetching the values from the storage.
+    // NOTE: all the keys of the storage are held in memory while replying, the backends only
+    //       returning them at once with get_all_entries.
+    async fn reply_keys_only(&self, q: &zenoh::queryable::Query) {
+        let storage = self.storage.lock().await;
+        let entries = match storage.get_all_entries().await {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!(
+                    "Storage '{}' raised an error while retrieving keys: {}",
+                    self.name,
+                    e
+                );
+                return;
+            }
+        };
+        drop(storage);
+        for (k, ts) in entries {
+            let full_key = match k {
+                Some(key) => StorageService::get_prefixed(&self.strip_prefix, &key.into()),
+                None => self.strip_prefix.clone().unwrap(),
+            };
+            if q.key_expr().intersects(&full_key) {
+                let value = Value::empty().encoding(Encoding::from(ELIDED_VALUE_ENCODING));
+                let sample = Sample::new(full_key, value).with_timestamp(ts);
+                if let Err(e) = q.reply(Ok(sample)).res().await {
+                    tracing::warn!(
+                        "Storage '{}' raised an error replying a query: {}",
+                        self.name,
+                        e
+                    )
+                }
+            }
+        }
+    }
+
     async fn get_matching_keys(&self, key_expr: &KeyExpr<'_>) -> Vec<OwnedKeyExpr> {
         let mut result = Vec::new();
         // @TODO: if cache exists, use that to get the list
@@ -636,7 +770,7 @@ impl StorageService {
         }
     }
 
-    async fn initialize_if_empty(&mut self) {
+    async fn initialize_if_empty(&self) {
         if self.replication.is_some() && self.replication.as_ref().unwrap().empty_start {
             // align with other storages, querying them on key_expr,
             // with `_time=[..]` to get historical data (in case of time-series)
@@ -699,6 +833,77 @@ fn construct_update(data: String) -> Update {
     Update { kind, data }
 }
 
+// Queue of the writes waiting for the rate limiter, committed by the writer task of the storage.
+// When the queue is full, the oldest pending write is dropped to make room for the incoming one:
+// the most recent updates are the ones eventually kept by the storage.
+struct WriteQueue {
+    max_writes_per_sec: u64,
+    tx: Sender<Sample>,
+    rx: Receiver<Sample>,
+}
+
+impl WriteQueue {
+    fn new(max_writes_per_sec: u64, capacity: usize) -> Self {
+        let (tx, rx) = flume::bounded(capacity);
+        Self {
+            max_writes_per_sec,
+            tx,
+            rx,
+        }
+    }
+
+    // Enqueue the sample, returning the pending one dropped to make room for it (if any)
+    fn push(&self, mut sample: Sample) -> Option<Sample> {
+        let mut dropped = None;
+        loop {
+            match self.tx.try_send(sample) {
+                Ok(()) => return dropped,
+                Err(TrySendError::Full(s)) => {
+                    // the writer may have taken a sample in the meantime, hence the retry
+                    dropped = self.rx.try_recv().ok().or(dropped);
+                    sample = s;
+                }
+                // the queue holds a receiver, it cannot be disconnected
+                Err(TrySendError::Disconnected(_)) => return dropped,
+            }
+        }
+    }
+}
+
+// Token bucket limiting the rate of writes committed to the storage, or of the values fetched to
+// reply wildcard queries. Bursts up to the configured rate are allowed, the bucket being refilled
+// continuously at that rate. The writes waiting for a token are held in a `WriteQueue`.
+struct WriteLimiter {
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl WriteLimiter {
+    fn new(max_writes_per_sec: u64) -> Self {
+        let rate = max_writes_per_sec as f64;
+        Self {
+            rate,
+            tokens: rate,
+            last_refill: Instant::now(),
+        }
+    }
+
+    async fn acquire(&mut self) {
+        loop {
+            let now = Instant::now();
+            let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+            self.last_refill = now;
+            if self.tokens >= 1.0 {
+                self.tokens -= 1.0;
+                return;
+            }
+            task::sleep(Duration::from_secs_f64((1.0 - self.tokens) / self.rate)).await;
+        }
+    }
+}
+
 // Periodic event cleaning-up data info for old metadata
 struct GarbageCollectionEvent {
     config: GarbageCollectionConfig,
@@ -743,3 +948,141 @@ impl Timed for GarbageCollectionEvent {
         tracing::trace!("End garbage collection of obsolete data-infos");
     }
 }
+
+// A storage holding values for `keys` keys, counting the values fetched but not replied yet
+#[cfg(test)]
+struct CountingStorage {
+    keys: usize,
+    fetched: Arc<std::sync::atomic::AtomicUsize>,
+    max_fetched: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+#[cfg(test)]
+#[async_trait]
+impl zenoh_backend_traits::Storage for CountingStorage {
+    fn get_admin_status(&self) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    async fn put(
+        &mut self,
+        _key: Option<OwnedKeyExpr>,
+        _value: Value,
+        _timestamp: Timestamp,
+    ) -> ZResult<StorageInsertionResult> {
+        bail!("read-only")
+    }
+
+    async fn delete(
+        &mut self,
+        _key: Option<OwnedKeyExpr>,
+        _timestamp: Timestamp,
+    ) -> ZResult<StorageInsertionResult> {
+        bail!("read-only")
+    }
+
+    async fn get(
+        &mut self,
+        key: Option<OwnedKeyExpr>,
+        _parameters: &str,
+    ) -> ZResult<Vec<StoredData>> {
+        use std::sync::atomic::Ordering;
+        let fetched = self.fetched.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_fetched.fetch_max(fetched, Ordering::SeqCst);
+        Ok(vec![StoredData {
+            value: Value::from(key.unwrap().as_str()),
+            timestamp: zenoh::time::new_reception_timestamp(),
+        }])
+    }
+
+    async fn get_all_entries(&self) -> ZResult<Vec<(Option<OwnedKeyExpr>, Timestamp)>> {
+        Ok((0..self.keys)
+            .map(|i| {
+                (
+                    Some(OwnedKeyExpr::new(format!("counting/{i}")).unwrap()),
+                    zenoh::time::new_reception_timestamp(),
+                )
+            })
+            .collect())
+    }
+}
+
+#[test]
+fn test_reply_wildcard_query_one_value_at_a_time() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    const KEYS: usize = 300;
+    const MAX_READS_PER_SEC: u64 = 100;
+
+    task::block_on(async {
+        zenoh_core::zasync_executor_init!();
+        let mut config = zenoh::config::peer();
+        config
+            .insert_json5("scouting/multicast/enabled", "false")
+            .unwrap();
+        let session = Arc::new(zenoh::open(config).res().await.unwrap());
+
+        let fetched = Arc::new(AtomicUsize::new(0));
+        let max_fetched = Arc::new(AtomicUsize::new(0));
+        let replied = Arc::new(AtomicUsize::new(0));
+        let (c_fetched, c_replied) = (fetched.clone(), replied.clone());
+        let storage = StorageService {
+            session: session.clone(),
+            key_expr: OwnedKeyExpr::new("counting/**").unwrap(),
+            complete: false,
+            name: "counting".into(),
+            strip_prefix: None,
+            storage: Mutex::new(Box::new(CountingStorage {
+                keys: KEYS,
+                fetched: fetched.clone(),
+                max_fetched: max_fetched.clone(),
+            })),
+            capability: Capability {
+                persistence: Persistence::Volatile,
+                history: History::Latest,
+                read_cost: 0,
+            },
+            tombstones: Arc::new(RwLock::new(KeBoxTree::default())),
+            wildcard_updates: Arc::new(RwLock::new(KeBoxTree::default())),
+            in_interceptor: None,
+            // called on each value right before replying it
+            out_interceptor: Some(Arc::new(move |sample| {
+                c_fetched.fetch_sub(1, Ordering::SeqCst);
+                c_replied.fetch_add(1, Ordering::SeqCst);
+                sample
+            })),
+            sample_interceptor: None,
+            write_queue: None,
+            reply_limiter: Some(Mutex::new(WriteLimiter::new(MAX_READS_PER_SEC))),
+            replication: None,
+        };
+        let (tx, rx) = flume::bounded(1);
+        let handle = task::spawn(
+            Arc::new(storage)
+                .start_storage_queryable_subscriber(rx, GarbageCollectionConfig::default()),
+        );
+        task::sleep(Duration::from_millis(100)).await;
+
+        let start = Instant::now();
+        let replies = session.get("counting/**").res().await.unwrap();
+        let mut count = 0;
+        while let Ok(reply) = replies.recv_async().await {
+            reply.sample.unwrap();
+            count += 1;
+        }
+        let elapsed = start.elapsed();
+
+        // each value is replied before the next one is fetched, they are never collected
+        assert_eq!(count, KEYS);
+        assert_eq!(replied.load(Ordering::SeqCst), KEYS);
+        assert_eq!(max_fetched.load(Ordering::SeqCst), 1);
+        // the first MAX_READS_PER_SEC values are a burst, the remaining ones are rate limited
+        let limited = (KEYS as u64 - MAX_READS_PER_SEC) as f64 / MAX_READS_PER_SEC as f64;
+        assert!(
+            elapsed >= Duration::from_secs_f64(limited * 0.9),
+            "{elapsed:?}"
+        );
+
+        tx.send_async(StorageMessage::Stop).await.unwrap();
+        handle.await;
+    });
+}
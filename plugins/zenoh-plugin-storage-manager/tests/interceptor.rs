@@ -0,0 +1,150 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test sample interceptors -
+// 1. samples failing a predicate are rejected and not stored
+// 2. samples are rewritten before being stored
+
+use std::sync::Arc;
+use std::thread::sleep;
+
+use async_std::task;
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::Config;
+use zenoh::query::Reply;
+use zenoh_core::zasync_executor_init;
+use zenoh_plugin_storage_manager::register_sample_interceptor;
+use zenoh_plugin_trait::Plugin;
+use zenoh_result::{zerror, ZResult};
+
+async fn put_data(session: &zenoh::Session, key_expr: &str, value: &str) {
+    println!("Putting Data ('{key_expr}': '{value}')...");
+    session.put(key_expr, value).res().await.unwrap();
+}
+
+async fn get_data(session: &zenoh::Session, key_expr: &str) -> Vec<Sample> {
+    let replies: Vec<Reply> = session
+        .get(key_expr)
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    println!("Getting replies on '{key_expr}': '{replies:?}'...");
+    let mut samples = Vec::new();
+    for reply in replies {
+        if let Ok(sample) = reply.sample {
+            samples.push(sample);
+        }
+    }
+    println!("Getting Data on '{key_expr}': '{samples:?}'...");
+    samples
+}
+
+async fn test_interceptors() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+
+    // rejects values which are not integers
+    register_sample_interceptor(
+        "integers_only",
+        Arc::new(|sample: Sample| -> ZResult<Sample> {
+            let value = sample.value.to_string();
+            match value.parse::<i64>() {
+                Ok(_) => Ok(sample),
+                Err(e) => Err(zerror!("'{}' is not an integer: {}", value, e).into()),
+            }
+        }),
+    );
+    // redacts the values
+    register_sample_interceptor(
+        "redact",
+        Arc::new(|mut sample: Sample| -> ZResult<Sample> {
+            sample.value = Value::from("<redacted>");
+            Ok(sample)
+        }),
+    );
+
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            r#"{
+                    storages: {
+                        reject_test: {
+                            key_expr: "interceptor/reject/**",
+                            volume: {
+                                id: "memory"
+                            },
+                            interceptor: "integers_only"
+                        },
+                        rewrite_test: {
+                            key_expr: "interceptor/rewrite/**",
+                            volume: {
+                                id: "memory"
+                            },
+                            interceptor: "redact"
+                        }
+                    }
+                }"#,
+        )
+        .unwrap();
+    let runtime = zenoh::runtime::RuntimeBuilder::new(config)
+        .build()
+        .await
+        .unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    sleep(std::time::Duration::from_secs(1));
+
+    put_data(&session, "interceptor/reject/a", "1").await;
+    put_data(&session, "interceptor/reject/b", "not a number").await;
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // expects exactly one sample, the rejected one not being stored
+    let data = get_data(&session, "interceptor/reject/**").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0].key_expr.as_str(), "interceptor/reject/a");
+    assert_eq!(format!("{}", data[0].value), "1");
+
+    // a rejected update does not override the stored value
+    put_data(&session, "interceptor/reject/a", "one").await;
+
+    sleep(std::time::Duration::from_millis(10));
+
+    let data = get_data(&session, "interceptor/reject/a").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "1");
+
+    put_data(&session, "interceptor/rewrite/a", "secret").await;
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // expects exactly one sample, with its value rewritten
+    let data = get_data(&session, "interceptor/rewrite/a").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "<redacted>");
+
+    drop(storage);
+}
+
+#[test]
+fn interceptor_test() {
+    task::block_on(async { test_interceptors().await });
+}
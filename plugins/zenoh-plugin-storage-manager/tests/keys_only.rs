@@ -0,0 +1,137 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test keys-only queries -
+// the storage replies with all the matching keys and their timestamps, but no payload
+
+use std::thread::sleep;
+
+use async_std::task;
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::Config;
+use zenoh::query::Reply;
+use zenoh_core::zasync_executor_init;
+use zenoh_plugin_storage_manager::{ELIDED_VALUE_ENCODING, KEYS_ONLY_SEL_PARAM};
+use zenoh_plugin_trait::Plugin;
+
+async fn put_data(session: &zenoh::Session, key_expr: &str, value: &str) {
+    println!("Putting Data ('{key_expr}': '{value}')...");
+    session.put(key_expr, value).res().await.unwrap();
+}
+
+async fn get_data(session: &zenoh::Session, selector: &str) -> Vec<Sample> {
+    let replies: Vec<Reply> = session
+        .get(selector)
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    println!("Getting replies on '{selector}': '{replies:?}'...");
+    let mut samples = Vec::new();
+    for reply in replies {
+        if let Ok(sample) = reply.sample {
+            samples.push(sample);
+        }
+    }
+    println!("Getting Data on '{selector}': '{samples:?}'...");
+    samples
+}
+
+async fn test_keys_only() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            r#"{
+                    storages: {
+                        keys_only_test: {
+                            key_expr: "keys_only/test/**",
+                            volume: {
+                                id: "memory"
+                            }
+                        }
+                    }
+                }"#,
+        )
+        .unwrap();
+    let runtime = zenoh::runtime::RuntimeBuilder::new(config)
+        .build()
+        .await
+        .unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    sleep(std::time::Duration::from_secs(1));
+
+    put_data(&session, "keys_only/test/a", "1").await;
+    put_data(&session, "keys_only/test/b", "2").await;
+    put_data(&session, "keys_only/test/c/d", "3").await;
+    put_data(&session, "keys_only/test/e", "").await;
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // expects all the keys, without payload
+    let mut data = get_data(
+        &session,
+        &format!("keys_only/test/**?{KEYS_ONLY_SEL_PARAM}=true"),
+    )
+    .await;
+    data.sort_by(|a, b| a.key_expr.as_str().cmp(b.key_expr.as_str()));
+    let keys: Vec<&str> = data.iter().map(|s| s.key_expr.as_str()).collect();
+    assert_eq!(
+        keys,
+        vec![
+            "keys_only/test/a",
+            "keys_only/test/b",
+            "keys_only/test/c/d",
+            "keys_only/test/e"
+        ]
+    );
+    for sample in data.iter() {
+        assert_eq!(sample.value.payload.len(), 0);
+        assert_eq!(sample.value.encoding, Encoding::from(ELIDED_VALUE_ENCODING));
+        assert!(sample.timestamp.is_some());
+    }
+
+    // an empty value stays distinguishable from an elided one
+    let data = get_data(&session, "keys_only/test/e").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0].value.payload.len(), 0);
+    assert_ne!(
+        data[0].value.encoding,
+        Encoding::from(ELIDED_VALUE_ENCODING)
+    );
+
+    // expects the values when not explicitly requesting keys only
+    let data = get_data(
+        &session,
+        &format!("keys_only/test/a?{KEYS_ONLY_SEL_PARAM}=false"),
+    )
+    .await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "1");
+
+    drop(storage);
+}
+
+#[test]
+fn keys_only_test() {
+    task::block_on(async { test_keys_only().await });
+}
@@ -0,0 +1,97 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test wildcard queries on a storage holding many keys -
+// the values are replied one at a time, while the storage keeps accepting writes
+
+use std::thread::sleep;
+
+use async_std::task;
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::Config;
+use zenoh_core::zasync_executor_init;
+use zenoh_plugin_trait::Plugin;
+
+const KEYS: usize = 1_000;
+
+async fn test_large_query() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            r#"{
+                    storages: {
+                        large_query_test: {
+                            key_expr: "large_query/test/**",
+                            volume: {
+                                id: "memory"
+                            }
+                        }
+                    }
+                }"#,
+        )
+        .unwrap();
+    let runtime = zenoh::runtime::RuntimeBuilder::new(config)
+        .build()
+        .await
+        .unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    sleep(std::time::Duration::from_secs(1));
+
+    for i in 0..KEYS {
+        session
+            .put(format!("large_query/test/{i}"), i.to_string())
+            .res()
+            .await
+            .unwrap();
+    }
+
+    sleep(std::time::Duration::from_millis(100));
+
+    // writes go on while the replies are streamed
+    let replies = session.get("large_query/test/**").res().await.unwrap();
+    session
+        .put("large_query/test/new", "new")
+        .res()
+        .await
+        .unwrap();
+
+    let mut values = Vec::new();
+    while let Ok(reply) = replies.recv_async().await {
+        let sample = reply.sample.unwrap();
+        if sample.key_expr.as_str() != "large_query/test/new" {
+            assert_eq!(
+                sample.key_expr.as_str(),
+                format!("large_query/test/{}", sample.value)
+            );
+            values.push(sample.value.to_string().parse::<usize>().unwrap());
+        }
+    }
+    values.sort_unstable();
+    assert_eq!(values, (0..KEYS).collect::<Vec<_>>());
+
+    drop(storage);
+}
+
+#[test]
+fn large_query_test() {
+    task::block_on(async { test_large_query().await });
+}
@@ -14,7 +14,7 @@
 
 // Test wild card updates -
 // 1. normal case, just some wild card puts and deletes on existing keys and ensure it works
-// 2. check for dealing with out of order updates
+// 2. check for dealing with out of order updates, with explicit timestamps
 
 use std::str::FromStr;
 use std::thread::sleep;
@@ -26,16 +26,24 @@ use zenoh::{prelude::Config, time::Timestamp};
 use zenoh_core::zasync_executor_init;
 use zenoh_plugin_trait::Plugin;
 
-async fn put_data(session: &zenoh::Session, key_expr: &str, value: &str, _timestamp: Timestamp) {
+async fn put_data(session: &zenoh::Session, key_expr: &str, value: &str, timestamp: Timestamp) {
     println!("Putting Data ('{key_expr}': '{value}')...");
-    //  @TODO: how to add timestamp metadata with put, not manipulating sample...
-    session.put(key_expr, value).res().await.unwrap();
+    session
+        .put(key_expr, value)
+        .with_timestamp(timestamp)
+        .res()
+        .await
+        .unwrap();
 }
 
-async fn delete_data(session: &zenoh::Session, key_expr: &str, _timestamp: Timestamp) {
+async fn delete_data(session: &zenoh::Session, key_expr: &str, timestamp: Timestamp) {
     println!("Deleting Data '{key_expr}'...");
-    //  @TODO: how to add timestamp metadata with delete, not manipulating sample...
-    session.delete(key_expr).res().await.unwrap();
+    session
+        .delete(key_expr)
+        .with_timestamp(timestamp)
+        .res()
+        .await
+        .unwrap();
 }
 
 async fn get_data(session: &zenoh::Session, key_expr: &str) -> Vec<Sample> {
@@ -144,7 +152,87 @@ async fn test_updates_in_order() {
     drop(storage);
 }
 
+async fn test_updates_out_of_order() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            r#"{
+                    storages: {
+                        operation_ooo_test: {
+                            key_expr: "operation/ooo/**",
+                            volume: {
+                                id: "memory"
+                            }
+                        }
+                    }
+                }"#,
+        )
+        .unwrap();
+
+    let runtime = zenoh::runtime::RuntimeBuilder::new(config)
+        .build()
+        .await
+        .unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    sleep(std::time::Duration::from_secs(1));
+
+    put_data(
+        &session,
+        "operation/ooo/a",
+        "2",
+        Timestamp::from_str("2022-01-17T10:43:10.418555997Z/BC779A06D7E049BD88C3FF3DB0C17FCC")
+            .unwrap(),
+    )
+    .await;
+
+    // an older put received afterwards does not override the latest value
+    put_data(
+        &session,
+        "operation/ooo/a",
+        "1",
+        Timestamp::from_str("2022-01-17T10:42:10.418555997Z/BC779A06D7E049BD88C3FF3DB0C17FCC")
+            .unwrap(),
+    )
+    .await;
+
+    sleep(std::time::Duration::from_millis(10));
+
+    let data = get_data(&session, "operation/ooo/a").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "2");
+
+    // an older delete received afterwards does not remove the latest value
+    delete_data(
+        &session,
+        "operation/ooo/a",
+        Timestamp::from_str("2022-01-17T10:42:40.418555997Z/BC779A06D7E049BD88C3FF3DB0C17FCC")
+            .unwrap(),
+    )
+    .await;
+
+    sleep(std::time::Duration::from_millis(10));
+
+    let data = get_data(&session, "operation/ooo/a").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "2");
+
+    drop(storage);
+}
+
 #[test]
 fn updates_test() {
     task::block_on(async { test_updates_in_order().await });
 }
+
+#[test]
+fn updates_out_of_order_test() {
+    task::block_on(async { test_updates_out_of_order().await });
+}
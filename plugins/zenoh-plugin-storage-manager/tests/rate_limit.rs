@@ -0,0 +1,142 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test write rate limiting -
+// a burst of puts is committed to the storage at the configured rate, none being dropped as long
+// as the queue of pending writes is not full, while the queries are still answered
+
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use async_std::task;
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::Config;
+use zenoh::query::Reply;
+use zenoh_core::zasync_executor_init;
+use zenoh_plugin_trait::Plugin;
+
+const MAX_WRITES_PER_SEC: usize = 10;
+const BURST: usize = 3 * MAX_WRITES_PER_SEC;
+const MAX_PENDING_WRITES: usize = 5;
+
+async fn get_data(session: &zenoh::Session, key_expr: &str) -> Vec<Sample> {
+    let replies: Vec<Reply> = session
+        .get(key_expr)
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    let mut samples = Vec::new();
+    for reply in replies {
+        if let Ok(sample) = reply.sample {
+            samples.push(sample);
+        }
+    }
+    println!("Getting {} samples on '{key_expr}'...", samples.len());
+    samples
+}
+
+async fn test_burst_is_smoothed() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            &format!(
+                r#"{{
+                    storages: {{
+                        rate_limit_test: {{
+                            key_expr: "rate_limit/test/**",
+                            volume: {{
+                                id: "memory"
+                            }},
+                            max_writes_per_sec: {MAX_WRITES_PER_SEC}
+                        }},
+                        rate_limit_overflow_test: {{
+                            key_expr: "rate_limit/overflow/**",
+                            volume: {{
+                                id: "memory"
+                            }},
+                            max_writes_per_sec: {MAX_WRITES_PER_SEC},
+                            max_pending_writes: {MAX_PENDING_WRITES}
+                        }}
+                    }}
+                }}"#
+            ),
+        )
+        .unwrap();
+    let runtime = zenoh::runtime::RuntimeBuilder::new(config)
+        .build()
+        .await
+        .unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    sleep(std::time::Duration::from_secs(1));
+
+    for i in 0..BURST {
+        session
+            .put(format!("rate_limit/test/{i}"), i.to_string())
+            .res()
+            .await
+            .unwrap();
+    }
+
+    // the query is answered while the writes are throttled, not after the pending ones
+    let start = Instant::now();
+    let data = get_data(&session, "rate_limit/test/**").await;
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(data.len() < BURST);
+
+    sleep(std::time::Duration::from_millis(500));
+
+    // the bucket allows one second worth of writes at once, then refills at the configured rate
+    let data = get_data(&session, "rate_limit/test/**").await;
+    assert!(data.len() >= MAX_WRITES_PER_SEC);
+    assert!(data.len() < BURST);
+
+    sleep(std::time::Duration::from_secs(3));
+
+    // expects the whole burst to be eventually stored
+    let data = get_data(&session, "rate_limit/test/**").await;
+    assert_eq!(data.len(), BURST);
+
+    for i in 0..BURST {
+        session
+            .put(format!("rate_limit/overflow/{i}"), i.to_string())
+            .res()
+            .await
+            .unwrap();
+    }
+
+    sleep(std::time::Duration::from_secs(3));
+
+    // the oldest pending writes are dropped once the queue is full, the latest one being kept
+    let data = get_data(&session, "rate_limit/overflow/**").await;
+    assert!(data.len() < BURST);
+    let last = format!("rate_limit/overflow/{}", BURST - 1);
+    assert!(data.iter().any(|s| s.key_expr.as_str() == last));
+
+    drop(storage);
+}
+
+#[test]
+fn rate_limit_test() {
+    task::block_on(async { test_burst_is_smoothed().await });
+}
@@ -23,6 +23,7 @@ use crate::prelude::*;
 use crate::sample::Attachment;
 use crate::sample::DataInfo;
 use crate::sample::QoS;
+use crate::time::Timestamp;
 use crate::Encoding;
 use crate::SessionRef;
 use crate::Undeclarable;
@@ -83,6 +84,7 @@ pub struct PutBuilder<'a, 'b> {
     pub(crate) publisher: PublisherBuilder<'a, 'b>,
     pub(crate) value: Value,
     pub(crate) kind: SampleKind,
+    pub(crate) timestamp: Option<Timestamp>,
     #[cfg(feature = "unstable")]
     pub(crate) attachment: Option<Attachment>,
 }
@@ -125,6 +127,15 @@ impl PutBuilder<'_, '_> {
         self
     }
 
+    /// Set the [`Timestamp`] of the written data instead of the one generated by the session,
+    /// e.g. to replay the history of a replicated storage.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn with_timestamp(mut self, timestamp: Timestamp) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
     #[zenoh_macros::unstable]
     pub fn with_attachment(mut self, attachment: Attachment) -> Self {
         self.attachment = Some(attachment);
@@ -159,6 +170,7 @@ impl SyncResolve for PutBuilder<'_, '_> {
             &publisher,
             self.value,
             self.kind,
+            self.timestamp,
             #[cfg(feature = "unstable")]
             self.attachment,
         )
@@ -654,6 +666,7 @@ impl SyncResolve for Publication<'_> {
             self.publisher,
             self.value,
             self.kind,
+            None,
             #[cfg(feature = "unstable")]
             self.attachment,
         )
@@ -827,6 +840,7 @@ fn resolve_put(
     publisher: &Publisher<'_>,
     value: Value,
     kind: SampleKind,
+    timestamp: Option<Timestamp>,
     #[cfg(feature = "unstable")] attachment: Option<Attachment>,
 ) -> ZResult<()> {
     tracing::trace!("write({:?}, [...])", &publisher.key_expr);
@@ -835,7 +849,7 @@ fn resolve_put(
         .as_ref()
         .unwrap()
         .clone();
-    let timestamp = publisher.session.runtime.new_timestamp();
+    let timestamp = timestamp.or_else(|| publisher.session.runtime.new_timestamp());
 
     if publisher.destination != Locality::SessionLocal {
         primitives.send_push(Push {
@@ -719,6 +719,7 @@ impl Session {
             publisher: self.declare_publisher(key_expr),
             value: value.into(),
             kind: SampleKind::Put,
+            timestamp: None,
             #[cfg(feature = "unstable")]
             attachment: None,
         }
@@ -753,6 +754,7 @@ impl Session {
             publisher: self.declare_publisher(key_expr),
             value: Value::empty(),
             kind: SampleKind::Delete,
+            timestamp: None,
             #[cfg(feature = "unstable")]
             attachment: None,
         }